@@ -0,0 +1,143 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Expr, ExprLit, ExprUnary, Fields, Lit, LitStr, Member, UnOp,
+};
+
+/// Codes of the range reserved by the specification that are not server errors.
+const RESERVED: std::ops::RangeInclusive<i32> = -32768..=-32100;
+/// Used by axum-jrpc for requests with an unsupported `Content-Type`.
+const UNSUPPORTED_CONTENT_TYPE: i32 = -32001;
+
+struct ErrorAttr {
+    code: i32,
+    data: Option<Member>,
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let arms = match &input.data {
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let attr = parse_attr(&variant.attrs, variant.span())?;
+                let variant_ident = &variant.ident;
+                arm(quote!(#ident::#variant_ident), &variant.fields, attr)
+            })
+            .collect::<syn::Result<Vec<_>>>()?,
+        Data::Struct(data) => {
+            let attr = parse_attr(&input.attrs, input.span())?;
+            vec![arm(quote!(#ident), &data.fields, attr)?]
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                input.span(),
+                "`JsonRpcError` can't be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for ::axum_jrpc::error::JsonRpcError #where_clause {
+            fn from(error: #ident #ty_generics) -> Self {
+                let message = ::std::string::ToString::to_string(&error);
+                let (reason, data) = match &error {
+                    #(#arms)*
+                };
+                ::axum_jrpc::error::JsonRpcError::new(reason, message, data)
+            }
+        }
+    })
+}
+
+fn arm(path: TokenStream, fields: &Fields, attr: ErrorAttr) -> syn::Result<TokenStream> {
+    let code = attr.code;
+    // Checked by `parse_code` to be a server or application error code.
+    let reason = quote!(::axum_jrpc::error::JsonRpcErrorReason::from(#code));
+    match attr.data {
+        Some(member) => {
+            let exists = fields
+                .iter()
+                .enumerate()
+                .any(|(index, field)| match &member {
+                    Member::Named(name) => field.ident.as_ref() == Some(name),
+                    Member::Unnamed(unnamed) => {
+                        field.ident.is_none() && unnamed.index as usize == index
+                    }
+                });
+            if !exists {
+                return Err(syn::Error::new(member.span(), "no such field"));
+            }
+            Ok(quote! {
+                #path { #member: data, .. } => (
+                    #reason,
+                    ::axum_jrpc::__private::serde_json::to_value(data).unwrap_or_default(),
+                ),
+            })
+        }
+        None => Ok(quote! {
+            #path { .. } => (#reason, ::axum_jrpc::__private::serde_json::Value::Null),
+        }),
+    }
+}
+
+fn parse_attr(attrs: &[Attribute], span: proc_macro2::Span) -> syn::Result<ErrorAttr> {
+    let mut code = None;
+    let mut data = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("jrpc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("code") {
+                let expr: Expr = meta.value()?.parse()?;
+                code = Some(parse_code(&expr)?);
+                Ok(())
+            } else if meta.path.is_ident("data") {
+                let field: LitStr = meta.value()?.parse()?;
+                data = Some(field.parse::<Member>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown jrpc attribute, expected `code` or `data`"))
+            }
+        })?;
+    }
+    let code = code.ok_or_else(|| syn::Error::new(span, "missing `#[jrpc(code = ...)]`"))?;
+    Ok(ErrorAttr { code, data })
+}
+
+fn parse_code(expr: &Expr) -> syn::Result<i32> {
+    let (negative, lit) = match expr {
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => (true, &**expr),
+        expr => (false, expr),
+    };
+    let code = match lit {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse::<i64>()?,
+        _ => return Err(syn::Error::new_spanned(expr, "expected an integer code")),
+    };
+    let code = i32::try_from(if negative { -code } else { code })
+        .map_err(|_| syn::Error::new_spanned(expr, "code is out of range for `i32`"))?;
+    if RESERVED.contains(&code) {
+        return Err(syn::Error::new_spanned(
+            expr,
+            format!(
+                "code {} is reserved for pre-defined errors, use -32099 to -32000 for server errors or a code outside -32768 to -32000",
+                code
+            ),
+        ));
+    }
+    if code == UNSUPPORTED_CONTENT_TYPE {
+        return Err(syn::Error::new_spanned(
+            expr,
+            "code -32001 is used by axum-jrpc for unsupported content types",
+        ));
+    }
+    Ok(code)
+}
@@ -0,0 +1,122 @@
+//! Macros for [axum-jrpc](https://docs.rs/axum-jrpc), re-exported by its `macros` feature.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemTrait};
+
+mod error;
+mod rpc;
+
+/// Generates a server and a typed client from a trait describing an API.
+///
+/// Every method must be `async`, take `&self` and arguments bound to plain identifiers, which
+/// are sent as named params and also accepted positionally, in declaration order. Methods
+/// returning `Result<T, E>` answer with `E` converted into a `JsonRpcError`. A method is
+/// called by its name unless renamed with `#[rpc(name = "...")]`.
+///
+/// For a trait `Math` this generates:
+/// - the trait itself, made an `async_trait` with `Send + Sync + 'static` bounds, and its
+///   provided `into_router` method returning a `JsonRpcRouter` that calls the server;
+/// - `MathClient<C>`, calling the methods through a `JsonRpcTransport` `C` and returning
+///   `Result<T, ClientError>`.
+///
+/// ```rust
+/// use axum_jrpc::error::JsonRpcError;
+/// use axum_jrpc::rpc;
+///
+/// #[rpc]
+/// pub trait Math {
+///     async fn add(&self, a: i32, b: i32) -> Result<i32, JsonRpcError>;
+///
+///     #[rpc(name = "math_double")]
+///     async fn double(&self, a: i32) -> i32;
+/// }
+///
+/// struct Calculator;
+///
+/// #[axum_jrpc::async_trait]
+/// impl Math for Calculator {
+///     async fn add(&self, a: i32, b: i32) -> Result<i32, JsonRpcError> {
+///         a.checked_add(b)
+///             .ok_or_else(|| JsonRpcError::invalid_params("Overflow"))
+///     }
+///
+///     async fn double(&self, a: i32) -> i32 {
+///         a * 2
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let router = Calculator.into_router();
+/// assert!(router.has_route("math_double"));
+///
+/// // The router is also an in-process transport.
+/// let client = MathClient::new(router);
+/// assert_eq!(client.add(1, 2).await.unwrap(), 3);
+/// assert_eq!(client.double(2).await.unwrap(), 4);
+/// assert!(client.add(i32::MAX, 1).await.is_err());
+/// # }
+/// ```
+///
+/// Arguments are deserialized from the params, so they can't borrow:
+///
+/// ```compile_fail
+/// #[axum_jrpc::rpc]
+/// pub trait Greeter {
+///     async fn greet(&self, name: &str) -> String;
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        let error = syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`#[rpc]` takes no arguments",
+        );
+        return error.into_compile_error().into();
+    }
+    let item = parse_macro_input!(item as ItemTrait);
+    rpc::expand(item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `From<T> for JsonRpcError`, with the `Display` output of `T` as the message.
+///
+/// Every variant of an enum, or a struct itself, needs a `#[jrpc(code = ...)]` attribute.
+/// `#[jrpc(data = "field")]` serializes a field, named or by index, as the error `data`.
+/// Codes in the range reserved by the specification, other than server errors
+/// (-32099 to -32000), are rejected at compile time.
+///
+/// ```rust
+/// use axum_jrpc::error::{JsonRpcError, JsonRpcErrorReason};
+///
+/// #[derive(Debug, thiserror::Error, JsonRpcError)]
+/// enum CustomError {
+///     #[error("Divisor must not be equal to 0")]
+///     #[jrpc(code = -32010)]
+///     DivideByZero,
+///     #[error("Account {name} not found")]
+///     #[jrpc(code = 404, data = "name")]
+///     NotFound { name: String },
+/// }
+///
+/// let error = JsonRpcError::from(CustomError::NotFound { name: "alice".to_owned() });
+/// assert_eq!(error.error_reason(), JsonRpcErrorReason::application_error(404).unwrap());
+/// ```
+///
+/// ```compile_fail
+/// use axum_jrpc::error::JsonRpcError;
+///
+/// #[derive(Debug, thiserror::Error, JsonRpcError)]
+/// #[error("Not found")]
+/// #[jrpc(code = -32601)]
+/// struct NotFound;
+/// ```
+#[proc_macro_derive(JsonRpcError, attributes(jrpc))]
+pub fn derive_json_rpc_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    error::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
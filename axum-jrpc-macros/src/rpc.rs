@@ -0,0 +1,253 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{
+    parse_quote, Attribute, FnArg, GenericArgument, Ident, ItemTrait, LitStr, Pat, PathArguments,
+    ReturnType, TraitItem, TraitItemFn, Type,
+};
+
+struct Method {
+    ident: Ident,
+    name: LitStr,
+    docs: Vec<Attribute>,
+    args: Vec<(Ident, Type)>,
+    /// The result type sent to clients.
+    ok: Type,
+    returns_result: bool,
+}
+
+pub(crate) fn expand(mut item: ItemTrait) -> syn::Result<TokenStream> {
+    if !item.generics.params.is_empty() || item.generics.where_clause.is_some() {
+        return Err(syn::Error::new(
+            item.generics.span(),
+            "generic rpc traits are not supported",
+        ));
+    }
+
+    let mut methods = Vec::new();
+    for trait_item in &mut item.items {
+        match trait_item {
+            TraitItem::Fn(method) => methods.push(parse_method(method)?),
+            other => {
+                return Err(syn::Error::new(
+                    other.span(),
+                    "rpc traits can only contain methods",
+                ))
+            }
+        }
+    }
+
+    let vis = &item.vis;
+    let trait_ident = &item.ident;
+    let module = format_ident!("__rpc_{}", trait_ident);
+    let client = format_ident!("{}Client", trait_ident);
+
+    let params = methods.iter().map(|method| {
+        let ident = &method.ident;
+        let args = method.args.iter().map(|(arg, ty)| quote!(pub(super) #arg: #ty));
+        quote! {
+            #[derive(::axum_jrpc::__private::serde::Serialize, ::axum_jrpc::__private::serde::Deserialize)]
+            #[serde(crate = "::axum_jrpc::__private::serde")]
+            pub(super) struct #ident { #(#args,)* }
+        }
+    });
+
+    let routes = methods.iter().map(|method| {
+        let ident = &method.ident;
+        let name = &method.name;
+        let args = method.args.iter().map(|(arg, _)| arg).collect::<Vec<_>>();
+        let mut call = quote!(__server.#ident(#(#args),*).await);
+        if !method.returns_result {
+            call = quote!(::core::result::Result::<_, ::axum_jrpc::error::JsonRpcError>::Ok(#call));
+        }
+        quote! {
+            .route(#name, {
+                let __server = ::std::sync::Arc::clone(&__server);
+                move |::axum_jrpc::handler::Params(__params): ::axum_jrpc::handler::Params<#module::#ident>| async move {
+                    let #module::#ident { #(#args),* } = __params;
+                    #call
+                }
+            })
+        }
+    });
+    item.items.push(parse_quote! {
+        /// Returns a router calling the methods of this server.
+        fn into_router(self) -> ::axum_jrpc::JsonRpcRouter
+        where
+            Self: Sized,
+        {
+            let __server = ::std::sync::Arc::new(self);
+            ::axum_jrpc::JsonRpcRouter::new() #(#routes)*
+        }
+    });
+
+    if item.colon_token.is_none() {
+        item.colon_token = Some(Default::default());
+    }
+    item.supertraits.push(parse_quote!(::core::marker::Send));
+    item.supertraits.push(parse_quote!(::core::marker::Sync));
+    item.supertraits.push(parse_quote!('static));
+
+    let calls = methods.iter().map(|method| {
+        let Method {
+            ident,
+            name,
+            docs,
+            ok,
+            ..
+        } = method;
+        let args = method.args.iter().map(|(arg, _)| arg);
+        let params = method.args.iter().map(|(arg, ty)| quote!(#arg: #ty));
+        quote! {
+            #(#docs)*
+            #vis async fn #ident(&self, #(#params),*) -> ::core::result::Result<#ok, ::axum_jrpc::client::ClientError> {
+                self.client.call(#name, #module::#ident { #(#args),* }).await
+            }
+        }
+    });
+    let client_doc = format!("Client for the [`{}`] API.", trait_ident);
+
+    Ok(quote! {
+        #[::axum_jrpc::async_trait]
+        #item
+
+        #[doc(hidden)]
+        #[allow(non_snake_case, non_camel_case_types, unused_imports)]
+        mod #module {
+            use super::*;
+
+            #(#params)*
+        }
+
+        #[doc = #client_doc]
+        #[derive(Debug)]
+        #vis struct #client<C> {
+            client: ::axum_jrpc::client::JsonRpcClient<C>,
+        }
+
+        impl<C: ::axum_jrpc::client::JsonRpcTransport> #client<C> {
+            #vis fn new(transport: C) -> Self {
+                Self {
+                    client: ::axum_jrpc::client::JsonRpcClient::new(transport),
+                }
+            }
+
+            #vis fn client(&self) -> &::axum_jrpc::client::JsonRpcClient<C> {
+                &self.client
+            }
+
+            #(#calls)*
+        }
+    })
+}
+
+fn parse_method(method: &mut TraitItemFn) -> syn::Result<Method> {
+    let sig = &method.sig;
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new(sig.span(), "rpc methods must be `async`"));
+    }
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            "generic rpc methods are not supported",
+        ));
+    }
+
+    let mut inputs = sig.inputs.iter();
+    match inputs.next() {
+        Some(FnArg::Receiver(receiver))
+            if receiver.reference.is_some() && receiver.mutability.is_none() => {}
+        _ => return Err(syn::Error::new(sig.span(), "rpc methods must take `&self`")),
+    }
+    let mut args = Vec::new();
+    for input in inputs {
+        let arg = match input {
+            FnArg::Typed(arg) => arg,
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new(receiver.span(), "unexpected receiver"))
+            }
+        };
+        // Params are deserialized into owned values and moved into the handler.
+        if let Type::Reference(ty) = &*arg.ty {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "rpc method arguments must be owned, e.g. `String` instead of `&str`",
+            ));
+        }
+        match &*arg.pat {
+            Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                args.push((pat.ident.clone(), (*arg.ty).clone()));
+            }
+            pat => {
+                return Err(syn::Error::new(
+                    pat.span(),
+                    "rpc method arguments must be identifiers",
+                ))
+            }
+        }
+    }
+
+    let (ok, returns_result) = match &sig.output {
+        ReturnType::Default => (parse_quote!(()), false),
+        ReturnType::Type(_, ty) => match result_ok_type(ty) {
+            Some(ok) => (ok, true),
+            None => ((**ty).clone(), false),
+        },
+    };
+
+    let mut name = LitStr::new(&sig.ident.to_string(), sig.ident.span());
+    let mut error = None;
+    method.attrs.retain(|attr| {
+        if !attr.path().is_ident("rpc") {
+            return true;
+        }
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unknown rpc method attribute, expected `name`"))
+            }
+        });
+        if let Err(e) = result {
+            error = Some(e);
+        }
+        false
+    });
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    Ok(Method {
+        ident: method.sig.ident.clone(),
+        name,
+        docs: method
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"))
+            .cloned()
+            .collect(),
+        args,
+        ok,
+        returns_result,
+    })
+}
+
+/// Returns `T` if `ty` is a `Result<T, ..>`.
+fn result_ok_type(ty: &Type) -> Option<Type> {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(ok) => Some(ok.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -1,9 +1,9 @@
-use axum::extract::ContentLengthLimit;
-use axum::routing::post;
+use axum::extract::DefaultBodyLimit;
 use axum::Router;
-use axum_jrpc::{JrpcResult, JsonRpcExtractor, JsonRpcResponse};
+use axum_jrpc::{JrpcResult, JsonRpcExtractor, JsonRpcRouter};
 
-use axum_jrpc::error::{JsonRpcError, JsonRpcErrorReason};
+use axum_jrpc::error::JsonRpcError;
+use axum_jrpc::handler::Params;
 use serde::Deserialize;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -17,7 +17,13 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let router = Router::new().route("/", post(handler));
+    let rpc = JsonRpcRouter::new()
+        .route("add", add)
+        .route("sub", sub)
+        .route("div", div);
+    let router = Router::new()
+        .route_service("/", rpc)
+        .layer(DefaultBodyLimit::max(1024));
 
     tracing::debug!("listening");
     axum::Server::bind(&"127.0.0.1:8080".parse().unwrap())
@@ -26,36 +32,28 @@ async fn main() {
         .unwrap();
 }
 
-async fn handler(
-    ContentLengthLimit(value): ContentLengthLimit<JsonRpcExtractor, 1024>,
-) -> JrpcResult {
-    let answer_id = value.get_answer_id();
-    println!("{:?}", value);
-    match value.method.as_str() {
-        "add" => {
-            let request: Test = value.parse_params()?;
-            let result = request.a + request.b;
-            Ok(JsonRpcResponse::success(answer_id, result))
-        }
-        "sub" => {
-            let result: [i32; 2] = value.parse_params()?;
-            let result = match failing_sub(result[0], result[1]).await {
-                Ok(result) => result,
-                Err(e) => return Err(JsonRpcResponse::error(answer_id, e.into())),
-            };
-            Ok(JsonRpcResponse::success(answer_id, result))
-        }
-        "div" => {
-            let result: [i32; 2] = value.parse_params()?;
-            let result = match failing_div(result[0], result[1]).await {
-                Ok(result) => result,
-                Err(e) => return Err(JsonRpcResponse::error(answer_id, e.into())),
-            };
+// Accepts both `[1, 2]` and `{"a": 1, "b": 2}`.
+async fn add(Params(request): Params<Test>) -> Result<i32, JsonRpcError> {
+    Ok(request.a + request.b)
+}
 
-            Ok(JsonRpcResponse::success(answer_id, result))
-        }
-        method => Ok(value.method_not_found(method)),
-    }
+async fn sub(value: JsonRpcExtractor) -> JrpcResult {
+    let result: [i32; 2] = value.parse_params()?;
+    let result = match failing_sub(result[0], result[1]).await {
+        Ok(result) => result,
+        Err(e) => return Err(value.error(e.into())),
+    };
+    Ok(value.success(result))
+}
+
+async fn div(value: JsonRpcExtractor) -> JrpcResult {
+    let result: [i32; 2] = value.parse_params()?;
+    let result = match failing_div(result[0], result[1]).await {
+        Ok(result) => result,
+        Err(e) => return Err(value.error(e.into())),
+    };
+
+    Ok(value.success(result))
 }
 
 async fn failing_sub(a: i32, b: i32) -> anyhow::Result<i32> {
@@ -77,18 +75,9 @@ struct Test {
     b: i32,
 }
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, thiserror::Error, JsonRpcError)]
 enum CustomError {
     #[error("Divisor must not be equal to 0")]
+    #[jrpc(code = -32099)]
     DivideByZero,
 }
-
-impl From<CustomError> for JsonRpcError {
-    fn from(error: CustomError) -> Self {
-        JsonRpcError::new(
-            JsonRpcErrorReason::ServerError(-32099),
-            error.to_string(),
-            serde_json::Value::Null,
-        )
-    }
-}
@@ -0,0 +1,175 @@
+//! Calling JSON-RPC methods, used by the clients generated with the `rpc` macro.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::body::{Body, HttpBody};
+use axum::http::{header, Request};
+use axum::BoxError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+use tower::ServiceExt;
+
+use crate::error::JsonRpcError;
+use crate::{Id, JsonRpcAnswer, JsonRpcResponse, JsonRpcRouter};
+
+/// Sends serialized requests to a server, e.g. over HTTP.
+#[async_trait::async_trait]
+pub trait JsonRpcTransport: Send + Sync {
+    /// Sends `request` and returns the body of the response, empty for notifications.
+    async fn send(&self, request: Vec<u8>) -> Result<Vec<u8>, BoxError>;
+}
+
+/// Calls the router in-process, e.g. for tests.
+#[async_trait::async_trait]
+impl<S> JsonRpcTransport for JsonRpcRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    async fn send(&self, request: Vec<u8>) -> Result<Vec<u8>, BoxError> {
+        let request = Request::post("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(request))?;
+        let mut body = self.clone().oneshot(request).await?.into_body();
+        let mut response = Vec::new();
+        while let Some(chunk) = body.data().await {
+            response.extend_from_slice(&chunk?);
+        }
+        Ok(response)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("Failed to send the request: {0}")]
+    Transport(BoxError),
+    #[error("Failed to parse the response: {0}")]
+    InvalidResponse(serde_json::Error),
+    #[error("Response id `{actual}` does not match the request id `{expected}`")]
+    IdMismatch { expected: Id, actual: Id },
+    #[error(transparent)]
+    Rpc(JsonRpcError),
+}
+
+#[derive(Serialize)]
+struct Call<'a, P> {
+    jsonrpc: &'static str,
+    method: &'a str,
+    params: P,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+}
+
+#[derive(Debug)]
+/// Makes JSON-RPC 2.0 calls through a [`JsonRpcTransport`], numbering them from 1.
+pub struct JsonRpcClient<T> {
+    transport: T,
+    next_id: AtomicU64,
+}
+
+impl<T: JsonRpcTransport> JsonRpcClient<T> {
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Calls `method` and deserializes its result.
+    pub async fn call<P, R>(&self, method: &str, params: P) -> Result<R, ClientError>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let response = self.send(method, params, Some(id)).await?;
+        let response: JsonRpcResponse =
+            serde_json::from_slice(&response).map_err(ClientError::InvalidResponse)?;
+        let expected = Id::from(id);
+        // Errors for requests the server could not read carry a `null` id.
+        let null_error =
+            response.id == Id::Null && matches!(response.result, JsonRpcAnswer::Error(_));
+        if response.id != expected && !null_error {
+            return Err(ClientError::IdMismatch {
+                expected,
+                actual: response.id,
+            });
+        }
+        match response.result {
+            JsonRpcAnswer::Result(result) => {
+                serde_json::from_value(result).map_err(ClientError::InvalidResponse)
+            }
+            JsonRpcAnswer::Error(error) => Err(ClientError::Rpc(error)),
+        }
+    }
+
+    /// Sends a notification to `method`, which gets no response.
+    pub async fn notify<P: Serialize>(&self, method: &str, params: P) -> Result<(), ClientError> {
+        self.send(method, params, None).await.map(drop)
+    }
+
+    async fn send<P: Serialize>(
+        &self,
+        method: &str,
+        params: P,
+        id: Option<u64>,
+    ) -> Result<Vec<u8>, ClientError> {
+        let request = Call {
+            jsonrpc: "2.0",
+            method,
+            params,
+            id,
+        };
+        let request =
+            serde_json::to_vec(&request).map_err(|e| ClientError::Transport(Box::new(e)))?;
+        self.transport
+            .send(request)
+            .await
+            .map_err(ClientError::Transport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Reply(&'static str);
+
+    #[async_trait::async_trait]
+    impl JsonRpcTransport for Reply {
+        async fn send(&self, _request: Vec<u8>) -> Result<Vec<u8>, BoxError> {
+            Ok(self.0.as_bytes().to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn checks_response_ids() {
+        let client = JsonRpcClient::new(Reply(r#"{"jsonrpc":"2.0","result":3,"id":1}"#));
+        assert_eq!(client.call::<_, i32>("add", [1, 2]).await.unwrap(), 3);
+        // The second call has id 2.
+        let error = client.call::<_, i32>("add", [1, 2]).await.unwrap_err();
+        assert!(
+            matches!(&error, ClientError::IdMismatch { expected, actual }
+                if *expected == Id::from(2) && *actual == Id::from(1)),
+            "{}",
+            error
+        );
+
+        let client = JsonRpcClient::new(Reply(r#"{"jsonrpc":"2.0","result":3,"id":null}"#));
+        let error = client.call::<_, i32>("add", [1, 2]).await.unwrap_err();
+        assert!(matches!(error, ClientError::IdMismatch { .. }), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn accepts_errors_without_an_id() {
+        let client = JsonRpcClient::new(Reply(
+            r#"{"jsonrpc":"2.0","error":{"code":-32700,"message":"Parse error"},"id":null}"#,
+        ));
+        let error = client.call::<_, i32>("add", [1, 2]).await.unwrap_err();
+        assert!(matches!(error, ClientError::Rpc(e) if e.code() == -32700));
+    }
+}
@@ -0,0 +1,687 @@
+//! Extractor configuration.
+//!
+//! Insert a [`JsonRpcConfig`] into the request extensions to change how requests are
+//! validated; requests without one use [`JsonRpcConfig::default`].
+//! [`JsonRpcLayer`] does that and also applies the config to the responses.
+//! ```rust
+//! use axum::{routing::post, Router};
+//! use axum_jrpc::config::{HttpStatusCodes, JsonRpcConfig, JsonRpcLayer};
+//! use axum_jrpc::{JrpcResult, JsonRpcExtractor};
+//!
+//! async fn handler(req: JsonRpcExtractor) -> JrpcResult {
+//!     let trace = req.extra().get("_trace").cloned();
+//!     Ok(req.success(trace))
+//! }
+//!
+//! let app: Router = Router::new()
+//!     .route("/", post(handler))
+//!     .layer(JsonRpcLayer::new(
+//!         JsonRpcConfig::default()
+//!             .strict(false)
+//!             .status_codes(HttpStatusCodes),
+//!     ));
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::body::{self, Bytes, Full};
+use axum::http::{header, Extensions, HeaderMap, HeaderValue, Request, StatusCode};
+use axum::response::Response;
+use pin_project_lite::pin_project;
+use serde_json::value::{to_raw_value, RawValue};
+use serde_json::{json, Value};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::error::{JsonRpcError, JsonRpcErrorReason, ServerErrorCode};
+use crate::{JsonRpcAnswer, JsonRpcBody, JsonRpcErrors};
+
+#[derive(Clone)]
+pub struct JsonRpcConfig {
+    strict: bool,
+    accept_v1: bool,
+    status_codes: Arc<dyn StatusCodePolicy>,
+    content_types: Arc<[String]>,
+    redaction: Option<Arc<dyn RedactionPolicy>>,
+}
+
+impl Default for JsonRpcConfig {
+    fn default() -> Self {
+        Self {
+            strict: true,
+            accept_v1: false,
+            status_codes: Arc::new(AlwaysOk),
+            content_types: Arc::from(
+                [
+                    "application/json",
+                    "application/json-rpc",
+                    "application/jsonrequest",
+                    "application/*+json",
+                ]
+                .map(str::to_owned),
+            ),
+            redaction: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for JsonRpcConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonRpcConfig")
+            .field("strict", &self.strict)
+            .field("accept_v1", &self.accept_v1)
+            .field("content_types", &self.content_types)
+            .field("redaction", &self.redaction.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl JsonRpcConfig {
+    /// In strict mode (the default) requests with members other than `jsonrpc`, `method`,
+    /// `params` and `id` are rejected. Otherwise they are available from
+    /// [`JsonRpcExtractor::extra`](crate::JsonRpcExtractor::extra).
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Also accept [JSON-RPC 1.0](https://www.jsonrpc.org/specification_v1) requests, i.e.
+    /// requests without a `jsonrpc` member. Their `params` must be an array and a `null` id
+    /// marks a notification. Disabled by default.
+    pub fn accept_v1(mut self, accept: bool) -> Self {
+        self.accept_v1 = accept;
+        self
+    }
+
+    pub fn accepts_v1(&self) -> bool {
+        self.accept_v1
+    }
+
+    /// Sets the policy choosing the HTTP status of error responses.
+    /// Defaults to [`AlwaysOk`]. Only applied by [`JsonRpcLayer`].
+    pub fn status_codes<P: StatusCodePolicy>(mut self, policy: P) -> Self {
+        self.status_codes = Arc::new(policy);
+        self
+    }
+
+    pub fn status_code(&self, reason: &JsonRpcErrorReason) -> StatusCode {
+        self.status_codes.status_code(reason)
+    }
+
+    /// Sets the accepted request media types, `application/json`, `application/json-rpc`,
+    /// `application/jsonrequest` and `application/*+json` by default. A `*+suffix` subtype
+    /// matches any subtype with that suffix, e.g. `application/vnd.api+json`.
+    /// [`JsonRpcLayer`] echoes the request's media type in the response.
+    pub fn content_types<I, T>(mut self, content_types: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.content_types = content_types
+            .into_iter()
+            .map(|content_type| content_type.into().to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    pub fn accepted_content_types(&self) -> impl Iterator<Item = &str> {
+        self.content_types.iter().map(String::as_str)
+    }
+
+    /// Hides the details of errors chosen by `policy` from clients, e.g.
+    /// [`RedactInternalErrors`]. Their message is replaced with a generic one and their
+    /// `data` with a `correlation_id`: the request's `X-Request-Id` header if it has at most
+    /// 128 ASCII letters, digits, `-`, `_`, `.` and `:`, otherwise a generated id.
+    /// The original error is logged with [`tracing`] under the same id.
+    /// Only applied by [`JsonRpcLayer`].
+    pub fn redact_errors<P: RedactionPolicy>(mut self, policy: P) -> Self {
+        self.redaction = Some(Arc::new(policy));
+        self
+    }
+
+    /// Returns the media type of the request if it is accepted, ignoring parameters like
+    /// `charset`.
+    fn matching_content_type<'h>(&self, headers: &'h HeaderMap) -> Option<&'h str> {
+        let content_type = headers.get(header::CONTENT_TYPE)?.to_str().ok()?;
+        let essence = content_type.split(';').next()?.trim();
+        self.accepted_content_types()
+            .any(|allowed| media_type_matches(allowed, essence))
+            .then_some(essence)
+    }
+
+    pub(crate) fn accepts_content_type(&self, headers: &HeaderMap) -> bool {
+        self.matching_content_type(headers).is_some()
+    }
+
+    pub(crate) fn from_extensions(extensions: &Extensions) -> Self {
+        extensions.get::<Self>().cloned().unwrap_or_default()
+    }
+}
+
+/// Returns `true` if `essence` is the media type `allowed`, or has the suffix of an
+/// `allowed` pattern like `application/*+json`.
+fn media_type_matches(allowed: &str, essence: &str) -> bool {
+    let (allowed_type, suffix) = match allowed.split_once("/*+") {
+        Some(pattern) => pattern,
+        None => return allowed.eq_ignore_ascii_case(essence),
+    };
+    match essence.split_once('/') {
+        Some((ty, subtype)) => {
+            ty.eq_ignore_ascii_case(allowed_type)
+                && subtype
+                    .rsplit_once('+')
+                    .is_some_and(|(name, essence_suffix)| {
+                        !name.is_empty() && essence_suffix.eq_ignore_ascii_case(suffix)
+                    })
+        }
+        None => false,
+    }
+}
+
+/// Chooses the HTTP status code of a single error response.
+/// Successful responses and batches are always sent with `200 OK`.
+pub trait StatusCodePolicy: Send + Sync + 'static {
+    fn status_code(&self, reason: &JsonRpcErrorReason) -> StatusCode;
+}
+
+impl<F> StatusCodePolicy for F
+where
+    F: Fn(&JsonRpcErrorReason) -> StatusCode + Send + Sync + 'static,
+{
+    fn status_code(&self, reason: &JsonRpcErrorReason) -> StatusCode {
+        self(reason)
+    }
+}
+
+/// Sends every error with `200 OK`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlwaysOk;
+
+impl StatusCodePolicy for AlwaysOk {
+    fn status_code(&self, _reason: &JsonRpcErrorReason) -> StatusCode {
+        StatusCode::OK
+    }
+}
+
+/// Maps errors to HTTP status codes as
+/// [JSON-RPC over HTTP](https://www.jsonrpc.org/historical/json-rpc-over-http.html) suggests:
+/// `400` for malformed requests and params, `404` for unknown methods and `500` otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpStatusCodes;
+
+impl StatusCodePolicy for HttpStatusCodes {
+    fn status_code(&self, reason: &JsonRpcErrorReason) -> StatusCode {
+        match reason {
+            JsonRpcErrorReason::ParseError
+            | JsonRpcErrorReason::InvalidRequest
+            | JsonRpcErrorReason::InvalidParams => StatusCode::BAD_REQUEST,
+            JsonRpcErrorReason::MethodNotFound => StatusCode::NOT_FOUND,
+            JsonRpcErrorReason::ServerError(ServerErrorCode::UNSUPPORTED_CONTENT_TYPE) => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            JsonRpcErrorReason::InternalError
+            | JsonRpcErrorReason::ServerError(_)
+            | JsonRpcErrorReason::Reserved(_)
+            | JsonRpcErrorReason::ApplicationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Chooses the errors whose details must not be sent to clients.
+pub trait RedactionPolicy: Send + Sync + 'static {
+    fn redact(&self, error: &JsonRpcError) -> bool;
+}
+
+impl<F> RedactionPolicy for F
+where
+    F: Fn(&JsonRpcError) -> bool + Send + Sync + 'static,
+{
+    fn redact(&self, error: &JsonRpcError) -> bool {
+        self(error)
+    }
+}
+
+/// Redacts `Internal error`s and server errors, which usually carry details of the
+/// implementation. Rejections of a request's `Content-Type` are left as they are.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RedactInternalErrors;
+
+impl RedactionPolicy for RedactInternalErrors {
+    fn redact(&self, error: &JsonRpcError) -> bool {
+        match error.error_reason() {
+            JsonRpcErrorReason::ServerError(ServerErrorCode::UNSUPPORTED_CONTENT_TYPE) => false,
+            JsonRpcErrorReason::InternalError | JsonRpcErrorReason::ServerError(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Replaces the errors chosen by `policy` and returns the rewritten body, `None` if there
+/// were none.
+fn redact(
+    errors: &mut JsonRpcErrors,
+    policy: &dyn RedactionPolicy,
+    correlation_id: &str,
+) -> Option<Bytes> {
+    let mut redacted = Vec::new();
+    for (index, response) in &mut errors.responses {
+        let error = match &mut response.result {
+            JsonRpcAnswer::Error(error) if policy.redact(error) => error,
+            _ => continue,
+        };
+        tracing::error!(
+            correlation_id,
+            code = error.code(),
+            data = %error.data(),
+            "{}",
+            error.message()
+        );
+        let reason = error.error_reason();
+        *error = JsonRpcError::new(reason, reason.to_string(), Value::Null)
+            .with_data(json!({ "correlation_id": correlation_id }));
+        redacted.push((
+            *index,
+            to_raw_value(response).expect("JSON-RPC responses serialize"),
+        ));
+    }
+    if redacted.is_empty() {
+        return None;
+    }
+    if !errors.batch {
+        let (_, response) = redacted.pop()?;
+        return Some(Bytes::from(response.get().to_owned()));
+    }
+    let mut responses: Vec<&RawValue> =
+        serde_json::from_slice(&errors.body).expect("JSON-RPC batch body is an array");
+    for (index, response) in &redacted {
+        responses[*index] = response;
+    }
+    serde_json::to_vec(&responses).ok().map(Bytes::from)
+}
+
+/// Longest `X-Request-Id` used as correlation id.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Returns the `X-Request-Id` of a request, `None` if it is missing, too long or contains
+/// characters other than ASCII letters, digits, `-`, `_`, `.` and `:`.
+fn request_id(headers: &HeaderMap) -> Option<&str> {
+    let id = headers.get("x-request-id")?.to_str().ok()?;
+    let valid = !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'));
+    valid.then_some(id)
+}
+
+/// Returns a random id for the logs of a request.
+fn correlation_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    format!("{:016x}", hasher.finish())
+}
+
+/// [`Layer`] inserting a [`JsonRpcConfig`] into every request and applying it to the
+/// responses.
+#[derive(Debug, Clone, Default)]
+pub struct JsonRpcLayer {
+    config: JsonRpcConfig,
+}
+
+impl JsonRpcLayer {
+    pub fn new(config: JsonRpcConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl<S> Layer<S> for JsonRpcLayer {
+    type Service = JsonRpcService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        JsonRpcService {
+            inner,
+            config: self.config.clone(),
+        }
+    }
+}
+
+/// Middleware created by [`JsonRpcLayer`].
+#[derive(Debug, Clone)]
+pub struct JsonRpcService<S> {
+    inner: S,
+    config: JsonRpcConfig,
+}
+
+impl<S, B> Service<Request<B>> for JsonRpcService<S>
+where
+    S: Service<Request<B>, Response = Response>,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let content_type = self
+            .config
+            .matching_content_type(req.headers())
+            .and_then(|content_type| HeaderValue::from_str(content_type).ok());
+        let request_id = request_id(req.headers()).map(str::to_owned);
+        req.extensions_mut().insert(self.config.clone());
+        ResponseFuture {
+            future: self.inner.call(req),
+            config: self.config.clone(),
+            content_type,
+            request_id,
+        }
+    }
+}
+
+pin_project! {
+    /// Response future of [`JsonRpcService`].
+    pub struct ResponseFuture<F> {
+        #[pin]
+        future: F,
+        config: JsonRpcConfig,
+        content_type: Option<HeaderValue>,
+        request_id: Option<String>,
+    }
+}
+
+impl<F> std::fmt::Debug for ResponseFuture<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseFuture").finish_non_exhaustive()
+    }
+}
+
+impl<F, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response, E>>,
+{
+    type Output = Result<Response, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut res = match this.future.poll(cx) {
+            Poll::Ready(res) => res?,
+            Poll::Pending => return Poll::Pending,
+        };
+        if let Some(reason) = res.extensions().get::<JsonRpcErrorReason>() {
+            *res.status_mut() = this.config.status_code(reason);
+        }
+        if let Some(policy) = &this.config.redaction {
+            if let Some(mut errors) = res.extensions_mut().remove::<JsonRpcErrors>() {
+                let correlation_id = this.request_id.take().unwrap_or_else(correlation_id);
+                if let Some(body) = redact(&mut errors, &**policy, &correlation_id) {
+                    res.headers_mut().remove(header::CONTENT_LENGTH);
+                    *res.body_mut() = body::boxed(Full::from(body));
+                }
+            }
+        }
+        if res.extensions().get::<JsonRpcBody>().is_some() {
+            if let Some(content_type) = this.content_type.take() {
+                res.headers_mut().insert(header::CONTENT_TYPE, content_type);
+            }
+        }
+        Poll::Ready(Ok(res))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use axum::body::{Body, HttpBody};
+    use axum::extract::FromRequest;
+    use axum::response::IntoResponse;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::{Id, JsonRpcBatchResponse, JsonRpcExtractor, JsonRpcResponse};
+
+    fn internal_error() -> Response {
+        let error = JsonRpcError::internal_error("password=hunter2");
+        JsonRpcResponse::error(Id::from(1), error).into_response()
+    }
+
+    async fn send<S>(
+        config: JsonRpcConfig,
+        request_id: Option<&'static str>,
+        service: S,
+    ) -> (StatusCode, Option<Value>)
+    where
+        S: Service<Request<Body>, Response = Response, Error = Infallible>,
+    {
+        let mut request = Request::post("/").header(header::CONTENT_TYPE, "application/json");
+        if let Some(request_id) = request_id {
+            request = request.header("x-request-id", request_id);
+        }
+        let response = JsonRpcLayer::new(config)
+            .layer(service)
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let mut body = response.into_body();
+        let mut bytes = Vec::new();
+        while let Some(chunk) = body.data().await {
+            bytes.extend_from_slice(&chunk.unwrap());
+        }
+        (status, serde_json::from_slice(&bytes).ok())
+    }
+
+    async fn call(config: JsonRpcConfig, request_id: Option<&'static str>) -> Value {
+        let service = service_fn(|_: Request<Body>| async { Ok(internal_error()) });
+        send(config, request_id, service).await.1.unwrap()
+    }
+
+    #[tokio::test]
+    async fn redacts_under_the_layer_only() {
+        let config = JsonRpcConfig::default().redact_errors(RedactInternalErrors);
+        let response = call(config, Some("req-1")).await;
+        assert_eq!(response["error"]["message"], "Internal error");
+        assert_eq!(response["error"]["data"]["correlation_id"], "req-1");
+
+        let response = call(JsonRpcConfig::default(), Some("req-1")).await;
+        assert_eq!(response["error"]["message"], "password=hunter2");
+    }
+
+    #[tokio::test]
+    async fn redacts_responses_written_on_other_threads() {
+        let service = service_fn(|_: Request<Body>| async {
+            Ok(tokio::task::spawn_blocking(internal_error).await.unwrap())
+        });
+        let config = JsonRpcConfig::default().redact_errors(RedactInternalErrors);
+        let (_, response) = send(config, None, service).await;
+        assert_eq!(response.unwrap()["error"]["message"], "Internal error");
+    }
+
+    #[tokio::test]
+    async fn redacts_only_chosen_errors_of_a_batch() {
+        let service = service_fn(|_: Request<Body>| async {
+            let batch = JsonRpcBatchResponse::Batch(vec![
+                JsonRpcResponse::success(Id::from(1), 1),
+                JsonRpcResponse::notification(),
+                JsonRpcResponse::error(Id::from(2), JsonRpcError::internal_error("secret")),
+                JsonRpcResponse::error(Id::from(3), JsonRpcError::invalid_params("bad")),
+            ]);
+            Ok(batch.into_response())
+        });
+        let config = JsonRpcConfig::default().redact_errors(RedactInternalErrors);
+        let (_, response) = send(config, Some("req-2"), service).await;
+        assert_eq!(
+            response.unwrap(),
+            json!([
+                {"jsonrpc": "2.0", "result": 1, "id": 1},
+                {
+                    "jsonrpc": "2.0",
+                    "error": {
+                        "code": -32603,
+                        "message": "Internal error",
+                        "data": {"correlation_id": "req-2"},
+                    },
+                    "id": 2,
+                },
+                {
+                    "jsonrpc": "2.0",
+                    "error": {"code": -32602, "message": "bad", "data": null},
+                    "id": 3,
+                },
+            ])
+        );
+    }
+
+    async fn answer(request: Request<Body>) -> Result<Response, Infallible> {
+        let response = match JsonRpcExtractor::from_request(request, &()).await {
+            Ok(req) => match req.method() {
+                "add" => match req.parse_params::<[i32; 2]>() {
+                    Ok(params) => req.success(params[0] + params[1]),
+                    Err(rejection) => rejection,
+                },
+                "fail" => req.error(JsonRpcError::internal_error("boom")),
+                method => req.method_not_found(method),
+            },
+            Err(rejection) => rejection,
+        };
+        Ok(response.into_response())
+    }
+
+    async fn status(config: JsonRpcConfig, content_type: &str, body: &'static str) -> StatusCode {
+        let request = Request::post("/")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body))
+            .unwrap();
+        JsonRpcLayer::new(config)
+            .layer(service_fn(answer))
+            .oneshot(request)
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn maps_errors_to_http_status_codes() {
+        let config = || JsonRpcConfig::default().status_codes(HttpStatusCodes);
+        let json = "application/json";
+        let cases = [
+            (
+                json,
+                r#"{"jsonrpc":"2.0","method":"add","params":[1,2],"id":1}"#,
+                200,
+            ),
+            (json, r#"{"jsonrpc":"2.0","method":"add","#, 400),
+            (json, r#"{"jsonrpc":"2.0","method":1,"id":1}"#, 400),
+            (
+                json,
+                r#"{"jsonrpc":"2.0","method":"add","params":["a"],"id":1}"#,
+                400,
+            ),
+            (json, r#"{"jsonrpc":"2.0","method":"sub","id":1}"#, 404),
+            (
+                "text/plain",
+                r#"{"jsonrpc":"2.0","method":"add","id":1}"#,
+                415,
+            ),
+            (json, r#"{"jsonrpc":"2.0","method":"fail","id":1}"#, 500),
+        ];
+        for (content_type, body, expected) in cases {
+            assert_eq!(
+                status(config(), content_type, body).await,
+                expected,
+                "{}",
+                body
+            );
+        }
+        // Notifications are never answered, whatever their outcome.
+        let body = r#"{"jsonrpc":"2.0","method":"fail"}"#;
+        assert_eq!(status(config(), json, body).await, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn sends_errors_with_200_by_default() {
+        let json = "application/json";
+        for (content_type, body) in [
+            (json, r#"{"jsonrpc":"2.0","method":"add","#),
+            (json, r#"{"jsonrpc":"2.0","method":"sub","id":1}"#),
+            ("text/plain", r#"{"jsonrpc":"2.0","method":"add","id":1}"#),
+            (json, r#"{"jsonrpc":"2.0","method":"fail","id":1}"#),
+        ] {
+            let status = status(JsonRpcConfig::default(), content_type, body).await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+        }
+        let body = r#"{"jsonrpc":"2.0","method":"fail"}"#;
+        let status = status(JsonRpcConfig::default(), json, body).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn replaces_invalid_request_ids() {
+        let config = JsonRpcConfig::default().redact_errors(RedactInternalErrors);
+        let response = call(config, Some("<script>")).await;
+        let correlation_id = response["error"]["data"]["correlation_id"]
+            .as_str()
+            .unwrap();
+        assert_eq!(correlation_id.len(), 16);
+    }
+
+    #[test]
+    fn accepts_json_suffixes_by_default() {
+        let content_type = |config: &JsonRpcConfig, content_type: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+            config.matching_content_type(&headers).map(str::to_owned)
+        };
+        let config = JsonRpcConfig::default();
+        assert_eq!(
+            content_type(&config, "application/vnd.api+json; charset=utf-8").as_deref(),
+            Some("application/vnd.api+json")
+        );
+        assert!(content_type(&config, "application/JSON").is_some());
+        assert!(content_type(&config, "application/+json").is_none());
+        assert!(content_type(&config, "text/vnd.api+json").is_none());
+        assert!(content_type(&config, "application/vnd.api+xml").is_none());
+
+        let config = JsonRpcConfig::default().content_types(["application/json"]);
+        assert!(content_type(&config, "application/vnd.api+json").is_none());
+    }
+
+    #[test]
+    fn validates_request_ids() {
+        let headers = |id: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-request-id", HeaderValue::from_str(id).unwrap());
+            headers
+        };
+        assert_eq!(
+            request_id(&headers("4bf92f35-77b3.a:1_0")),
+            Some("4bf92f35-77b3.a:1_0")
+        );
+        assert_eq!(request_id(&headers("")), None);
+        assert_eq!(request_id(&headers("a b")), None);
+        assert_eq!(request_id(&headers("\"}")), None);
+        assert_eq!(
+            request_id(&headers(&"a".repeat(MAX_REQUEST_ID_LEN))).map(str::len),
+            Some(128)
+        );
+        assert_eq!(
+            request_id(&headers(&"a".repeat(MAX_REQUEST_ID_LEN + 1))),
+            None
+        );
+        assert_eq!(request_id(&HeaderMap::new()), None);
+    }
+}
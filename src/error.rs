@@ -1,14 +1,22 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+#[cfg(feature = "macros")]
+pub use axum_jrpc_macros::JsonRpcError;
+
 /// Constants for [error object](https://www.jsonrpc.org/specification#error_object)
 pub const INVALID_REQUEST: i32 = -32600;
 pub const METHOD_NOT_FOUND: i32 = -32601;
 pub const INVALID_PARAMS: i32 = -32602;
 pub const INTERNAL_ERROR: i32 = -32603;
 pub const PARSE_ERROR: i32 = -32700;
+/// Server error returned when the request is not sent with a JSON `Content-Type`
+pub const UNSUPPORTED_CONTENT_TYPE: i32 = -32001;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Classification of an error code. Converting to `i32` and back always returns the same
+/// reason, as [`ServerErrorCode`], [`ReservedErrorCode`] and [`ApplicationErrorCode`] can
+/// only hold codes of their range.
 pub enum JsonRpcErrorReason {
     ParseError,
     InvalidRequest,
@@ -16,9 +24,11 @@ pub enum JsonRpcErrorReason {
     InvalidParams,
     InternalError,
     /// -32000 to -32099
-    ServerError(i32),
+    ServerError(ServerErrorCode),
+    /// Other codes from -32768 to -32100, reserved by the specification without a meaning
+    Reserved(ReservedErrorCode),
     /// All other space
-    ApplicationError(i32),
+    ApplicationError(ApplicationErrorCode),
 }
 
 impl std::fmt::Display for JsonRpcErrorReason {
@@ -30,6 +40,7 @@ impl std::fmt::Display for JsonRpcErrorReason {
             JsonRpcErrorReason::InvalidParams => write!(f, "Invalid params"),
             JsonRpcErrorReason::InternalError => write!(f, "Internal error"),
             JsonRpcErrorReason::ServerError(code) => write!(f, "Server error: {}", code),
+            JsonRpcErrorReason::Reserved(code) => write!(f, "Reserved error: {}", code),
             JsonRpcErrorReason::ApplicationError(code) => {
                 write!(f, "Application error: {}", code)
             }
@@ -45,42 +56,238 @@ impl From<JsonRpcErrorReason> for i32 {
             JsonRpcErrorReason::MethodNotFound => METHOD_NOT_FOUND,
             JsonRpcErrorReason::InvalidParams => INVALID_PARAMS,
             JsonRpcErrorReason::InternalError => INTERNAL_ERROR,
-            JsonRpcErrorReason::ServerError(code) | JsonRpcErrorReason::ApplicationError(code) => {
-                code
-            }
+            JsonRpcErrorReason::ServerError(code) => code.code(),
+            JsonRpcErrorReason::Reserved(code) => code.code(),
+            JsonRpcErrorReason::ApplicationError(code) => code.code(),
         }
     }
 }
 
-impl JsonRpcErrorReason {
-    fn new(code: i32) -> Self {
+/// Classifies any code, e.g. of a deserialized error.
+/// Codes reserved by the specification without a meaning are [`Reserved`].
+///
+/// [`Reserved`]: JsonRpcErrorReason::Reserved
+impl From<i32> for JsonRpcErrorReason {
+    fn from(code: i32) -> Self {
         match code {
             PARSE_ERROR => Self::ParseError,
             INVALID_REQUEST => Self::InvalidRequest,
             METHOD_NOT_FOUND => Self::MethodNotFound,
             INVALID_PARAMS => Self::InvalidParams,
             INTERNAL_ERROR => Self::InternalError,
-            -32099..=-32000 => Self::ServerError(code),
-            _ => Self::ApplicationError(code),
+            SERVER_ERROR_START..=SERVER_ERROR_END => Self::ServerError(ServerErrorCode(code)),
+            RESERVED_START..=SERVER_ERROR_END => Self::Reserved(ReservedErrorCode(code)),
+            _ => Self::ApplicationError(ApplicationErrorCode(code)),
+        }
+    }
+}
+
+impl JsonRpcErrorReason {
+    /// Returns a server error, or an error if `code` is not from -32099 to -32000.
+    pub fn server_error(code: i32) -> Result<Self, InvalidErrorCode> {
+        ServerErrorCode::new(code).map(Self::ServerError)
+    }
+
+    /// Returns an application error, or an error if `code` is from the range reserved by
+    /// the specification, -32768 to -32000.
+    pub fn application_error(code: i32) -> Result<Self, InvalidErrorCode> {
+        ApplicationErrorCode::new(code).map(Self::ApplicationError)
+    }
+}
+
+const SERVER_ERROR_START: i32 = -32099;
+const SERVER_ERROR_END: i32 = -32000;
+const RESERVED_START: i32 = -32768;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("Invalid error code {code}, expected {expected}")]
+pub struct InvalidErrorCode {
+    code: i32,
+    expected: &'static str,
+}
+
+impl InvalidErrorCode {
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Code of a [server error](JsonRpcErrorReason::ServerError), from -32099 to -32000.
+pub struct ServerErrorCode(i32);
+
+impl ServerErrorCode {
+    /// Server error returned when the request is not sent with a JSON `Content-Type`
+    pub const UNSUPPORTED_CONTENT_TYPE: Self = Self(UNSUPPORTED_CONTENT_TYPE);
+
+    pub const fn new(code: i32) -> Result<Self, InvalidErrorCode> {
+        match code {
+            SERVER_ERROR_START..=SERVER_ERROR_END => Ok(Self(code)),
+            _ => Err(InvalidErrorCode {
+                code,
+                expected: "a server error code from -32099 to -32000",
+            }),
+        }
+    }
+
+    pub const fn code(self) -> i32 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for ServerErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Code of a [reserved error](JsonRpcErrorReason::Reserved), only obtained by classifying a
+/// code, e.g. of an error received from another server.
+pub struct ReservedErrorCode(i32);
+
+impl ReservedErrorCode {
+    pub const fn code(self) -> i32 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for ReservedErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Code of an [application error](JsonRpcErrorReason::ApplicationError), outside of the
+/// range reserved by the specification.
+pub struct ApplicationErrorCode(i32);
+
+impl ApplicationErrorCode {
+    pub const fn new(code: i32) -> Result<Self, InvalidErrorCode> {
+        match code {
+            RESERVED_START..=SERVER_ERROR_END => Err(InvalidErrorCode {
+                code,
+                expected: "an application error code outside of -32768 to -32000",
+            }),
+            _ => Ok(Self(code)),
         }
     }
+
+    pub const fn code(self) -> i32 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for ApplicationErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
 }
 
 #[derive(Debug, Error, Serialize, Deserialize)]
 pub struct JsonRpcError {
     code: i32,
     message: String,
+    #[serde(default)]
     data: serde_json::Value,
 }
 
 impl JsonRpcError {
-    pub fn new(code: JsonRpcErrorReason, message: String, data: serde_json::Value) -> Self {
+    pub fn new(
+        code: JsonRpcErrorReason,
+        message: impl Into<String>,
+        data: serde_json::Value,
+    ) -> Self {
         Self {
             code: code.into(),
-            message,
+            message: message.into(),
             data,
         }
     }
+
+    pub fn parse_error(message: impl Into<String>) -> Self {
+        Self::new(
+            JsonRpcErrorReason::ParseError,
+            message,
+            serde_json::Value::Null,
+        )
+    }
+
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(
+            JsonRpcErrorReason::InvalidRequest,
+            message,
+            serde_json::Value::Null,
+        )
+    }
+
+    pub fn method_not_found(message: impl Into<String>) -> Self {
+        Self::new(
+            JsonRpcErrorReason::MethodNotFound,
+            message,
+            serde_json::Value::Null,
+        )
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(
+            JsonRpcErrorReason::InvalidParams,
+            message,
+            serde_json::Value::Null,
+        )
+    }
+
+    pub fn internal_error(message: impl Into<String>) -> Self {
+        Self::new(
+            JsonRpcErrorReason::InternalError,
+            message,
+            serde_json::Value::Null,
+        )
+    }
+
+    pub fn server_error(code: ServerErrorCode, message: impl Into<String>) -> Self {
+        Self::new(
+            JsonRpcErrorReason::ServerError(code),
+            message,
+            serde_json::Value::Null,
+        )
+    }
+
+    pub fn application_error(code: ApplicationErrorCode, message: impl Into<String>) -> Self {
+        Self::new(
+            JsonRpcErrorReason::ApplicationError(code),
+            message,
+            serde_json::Value::Null,
+        )
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Sets `data` to the serialized value, or `null` if it can't be serialized.
+    /// ```rust
+    /// use axum_jrpc::error::{JsonRpcError, ServerErrorCode};
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// struct Balance {
+    ///     required: u64,
+    ///     available: u64,
+    /// }
+    ///
+    /// let balance = Balance { required: 10, available: 3 };
+    /// let code = ServerErrorCode::new(-32010).unwrap();
+    /// let error = JsonRpcError::server_error(code, "Insufficient funds").with_data(&balance);
+    /// assert_eq!(error.message(), "Insufficient funds");
+    /// assert_eq!(error.data_as::<Balance>().unwrap(), balance);
+    /// ```
+    pub fn with_data<T: Serialize>(mut self, data: T) -> Self {
+        self.data = serde_json::to_value(data).unwrap_or_default();
+        self
+    }
 }
 
 impl std::fmt::Display for JsonRpcError {
@@ -88,31 +295,403 @@ impl std::fmt::Display for JsonRpcError {
         write!(
             f,
             "{}: {}",
-            JsonRpcErrorReason::new(self.code),
+            JsonRpcErrorReason::from(self.code),
             self.message
         )
     }
 }
 
-#[cfg(feature = "anyhow_error")]
-impl From<anyhow::Error> for JsonRpcError {
-    fn from(error: anyhow::Error) -> Self {
-        let message = error.to_string();
-        let data = serde_json::Value::Null;
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// `Method not found` error, optionally listing similar method names in its `data`.
+/// ```rust
+/// use axum_jrpc::error::{JsonRpcError, JsonRpcErrorReason, MethodNotFound};
+///
+/// let error: JsonRpcError = MethodNotFound::new("eth_getBalanse")
+///     .suggest(["eth_getBalance", "eth_blockNumber"])
+///     .into();
+/// assert_eq!(error.error_reason(), JsonRpcErrorReason::MethodNotFound);
+/// ```
+pub struct MethodNotFound {
+    method: String,
+    suggestions: Vec<String>,
+}
+
+impl MethodNotFound {
+    pub fn new(method: &str) -> Self {
         Self {
-            code: 1,
-            message,
+            method: method.to_owned(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Suggests up to three of `methods` that are spelled like the requested method.
+    pub fn suggest<I>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let max_distance = (self.method.chars().count() / 3).max(2);
+        let mut similar = methods
+            .into_iter()
+            .filter_map(|method| {
+                let method = method.as_ref();
+                let distance = edit_distance(&self.method, method);
+                (distance <= max_distance).then(|| (distance, method.to_owned()))
+            })
+            .collect::<Vec<_>>();
+        similar.sort();
+        self.suggestions = similar
+            .into_iter()
+            .map(|(_, method)| method)
+            .take(3)
+            .collect();
+        self
+    }
+
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+}
+
+impl From<MethodNotFound> for JsonRpcError {
+    fn from(error: MethodNotFound) -> Self {
+        let data = if error.suggestions.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::json!({ "suggestions": error.suggestions })
+        };
+        JsonRpcError::new(
+            JsonRpcErrorReason::MethodNotFound,
+            format!("Method `{}` not found", error.method),
             data,
+        )
+    }
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
         }
     }
+    row[b.len()]
+}
+
+#[cfg(feature = "anyhow_error")]
+static ANYHOW_CONVERSION: std::sync::OnceLock<AnyhowConversion> = std::sync::OnceLock::new();
+
+#[cfg(feature = "anyhow_error")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How `From<anyhow::Error> for JsonRpcError` builds errors, set once at startup with
+/// [`install`](AnyhowConversion::install).
+///
+/// By default errors are `Internal error`s with the outermost context as the message and
+/// `null` data.
+/// ```rust
+/// use axum_jrpc::error::{AnyhowConversion, JsonRpcError, JsonRpcErrorReason};
+///
+/// AnyhowConversion::new()
+///     .reason(JsonRpcErrorReason::server_error(-32050).unwrap())
+///     .unwrap()
+///     .include_chain(true)
+///     .install()
+///     .unwrap();
+///
+/// let error = anyhow::anyhow!("connection refused").context("Failed to load the account");
+/// let error = JsonRpcError::from(error);
+/// assert_eq!(error.code(), -32050);
+/// assert_eq!(error.message(), "Failed to load the account");
+/// assert_eq!(error.data()["chain"], "Failed to load the account: connection refused");
+/// ```
+pub struct AnyhowConversion {
+    reason: JsonRpcErrorReason,
+    chain: bool,
+    backtrace: bool,
+}
+
+#[cfg(feature = "anyhow_error")]
+impl AnyhowConversion {
+    pub const fn new() -> Self {
+        Self {
+            reason: JsonRpcErrorReason::InternalError,
+            chain: false,
+            backtrace: false,
+        }
+    }
+
+    /// Sets the reason, and so the code, of converted errors. Only internal, server and
+    /// application errors are allowed; the others describe a bad request.
+    pub fn reason(mut self, reason: JsonRpcErrorReason) -> Result<Self, InvalidErrorCode> {
+        match reason {
+            JsonRpcErrorReason::InternalError
+            | JsonRpcErrorReason::ServerError(_)
+            | JsonRpcErrorReason::ApplicationError(_) => {
+                self.reason = reason;
+                Ok(self)
+            }
+            _ => Err(InvalidErrorCode {
+                code: reason.into(),
+                expected: "an internal, server or application error code",
+            }),
+        }
+    }
+
+    /// Adds the `{:#}` formatted error with all its causes to `data` as `chain`.
+    pub fn include_chain(mut self, chain: bool) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Adds the backtrace to `data` as `backtrace`, if one was captured.
+    /// Ignored in release builds.
+    pub fn include_backtrace(mut self, backtrace: bool) -> Self {
+        self.backtrace = backtrace;
+        self
+    }
+
+    /// Uses this conversion for all following `From<anyhow::Error>` conversions.
+    /// Fails if a conversion was already installed.
+    pub fn install(self) -> Result<(), AlreadyInstalled> {
+        ANYHOW_CONVERSION.set(self).map_err(|_| AlreadyInstalled)
+    }
+
+    /// Returns the installed conversion, the default one if none was installed.
+    pub fn installed() -> Self {
+        ANYHOW_CONVERSION.get().copied().unwrap_or_default()
+    }
+
+    pub fn convert(&self, error: &anyhow::Error) -> JsonRpcError {
+        let mut data = serde_json::Map::new();
+        if self.chain {
+            data.insert("chain".to_owned(), format!("{:#}", error).into());
+        }
+        #[cfg(debug_assertions)]
+        if self.backtrace {
+            let backtrace = error.backtrace();
+            if backtrace.status() == std::backtrace::BacktraceStatus::Captured {
+                data.insert("backtrace".to_owned(), backtrace.to_string().into());
+            }
+        }
+        let data = if data.is_empty() {
+            serde_json::Value::Null
+        } else {
+            data.into()
+        };
+        JsonRpcError::new(self.reason, error.to_string(), data)
+    }
+}
+
+#[cfg(feature = "anyhow_error")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("An anyhow conversion is already installed")]
+pub struct AlreadyInstalled;
+
+#[cfg(feature = "anyhow_error")]
+impl Default for AnyhowConversion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "anyhow_error")]
+impl From<anyhow::Error> for JsonRpcError {
+    fn from(error: anyhow::Error) -> Self {
+        AnyhowConversion::installed().convert(&error)
+    }
 }
 
 impl JsonRpcError {
     pub fn error_reason(&self) -> JsonRpcErrorReason {
-        JsonRpcErrorReason::new(self.code)
+        JsonRpcErrorReason::from(self.code)
     }
 
     pub fn code(&self) -> i32 {
         self.code
     }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn data(&self) -> &serde_json::Value {
+        &self.data
+    }
+
+    /// Deserializes `data`, e.g. from the error of a response.
+    pub fn data_as<'a, T: Deserialize<'a>>(&'a self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.data)
+    }
+
+    pub fn into_data(self) -> serde_json::Value {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reasons_round_trip() {
+        for code in [
+            i32::MIN,
+            -32769,
+            -32768,
+            PARSE_ERROR,
+            INVALID_REQUEST,
+            INTERNAL_ERROR,
+            -32650,
+            -32100,
+            -32099,
+            UNSUPPORTED_CONTENT_TYPE,
+            -32000,
+            -31999,
+            0,
+            i32::MAX,
+        ] {
+            assert_eq!(i32::from(JsonRpcErrorReason::from(code)), code);
+        }
+    }
+
+    #[test]
+    fn classifies_range_boundaries() {
+        assert!(matches!(
+            JsonRpcErrorReason::from(-32769),
+            JsonRpcErrorReason::ApplicationError(_)
+        ));
+        assert!(matches!(
+            JsonRpcErrorReason::from(-32768),
+            JsonRpcErrorReason::Reserved(_)
+        ));
+        assert!(matches!(
+            JsonRpcErrorReason::from(-32650),
+            JsonRpcErrorReason::Reserved(_)
+        ));
+        assert!(matches!(
+            JsonRpcErrorReason::from(-32100),
+            JsonRpcErrorReason::Reserved(_)
+        ));
+        assert_eq!(
+            JsonRpcErrorReason::from(-32099),
+            JsonRpcErrorReason::server_error(-32099).unwrap()
+        );
+        assert_eq!(
+            JsonRpcErrorReason::from(-32000),
+            JsonRpcErrorReason::server_error(-32000).unwrap()
+        );
+        assert_eq!(
+            JsonRpcErrorReason::from(-31999),
+            JsonRpcErrorReason::application_error(-31999).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_codes_outside_of_their_range() {
+        for code in [-32768, -32100, -32099, -32000] {
+            assert!(JsonRpcErrorReason::application_error(code).is_err());
+        }
+        for code in [-32768, -32100, -31999] {
+            assert!(JsonRpcErrorReason::server_error(code).is_err());
+        }
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("flaw", "lawn"), 2);
+        assert_eq!(edit_distance("héllo", "hello"), 1);
+    }
+
+    #[test]
+    fn suggests_the_closest_methods() {
+        let error = MethodNotFound::new("get_balanse").suggest([
+            "get_balance",
+            "get_balances",
+            "set_balance",
+            "get_block",
+            "get_balanse_of",
+            "send",
+        ]);
+        assert_eq!(
+            error.suggestions(),
+            ["get_balance", "get_balances", "set_balance"]
+        );
+
+        let error = MethodNotFound::new("a").suggest(["a", "b", "c", "d"]);
+        assert_eq!(error.suggestions(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn suggestions_are_cut_off_by_distance() {
+        // Short names allow two edits.
+        let error = MethodNotFound::new("add").suggest(["ad", "adds", "sub", "a"]);
+        assert_eq!(error.suggestions(), ["ad", "adds", "a"]);
+        // Longer ones a third of their length.
+        let error = MethodNotFound::new("eth_getBalance").suggest([
+            "eth_getBalanceWXYZ",
+            "eth_getBalanceVWXYZ",
+            "eth_blockNumber",
+        ]);
+        assert_eq!(error.suggestions(), ["eth_getBalanceWXYZ"]);
+        assert!(MethodNotFound::new("add")
+            .suggest(Vec::<String>::new())
+            .suggestions()
+            .is_empty());
+    }
+
+    #[test]
+    fn suggestions_are_sent_as_data() {
+        let error: JsonRpcError = MethodNotFound::new("ad").suggest(["add", "sub"]).into();
+        assert_eq!(error.error_reason(), JsonRpcErrorReason::MethodNotFound);
+        assert_eq!(error.data(), &serde_json::json!({ "suggestions": ["add"] }));
+
+        let error: JsonRpcError = MethodNotFound::new("ad").suggest(["mul"]).into();
+        assert_eq!(error.data(), &serde_json::Value::Null);
+    }
+
+    #[cfg(feature = "anyhow_error")]
+    #[test]
+    fn anyhow_conversions_are_installed_once() {
+        let conversion = AnyhowConversion::new().include_chain(true);
+        assert_eq!(conversion.install(), Ok(()));
+        assert_eq!(AnyhowConversion::new().install(), Err(AlreadyInstalled));
+        assert_eq!(AnyhowConversion::installed(), conversion);
+    }
+
+    #[cfg(feature = "anyhow_error")]
+    #[test]
+    fn anyhow_conversions_only_use_error_reasons_of_the_server() {
+        for reason in [
+            JsonRpcErrorReason::InternalError,
+            JsonRpcErrorReason::server_error(-32050).unwrap(),
+            JsonRpcErrorReason::application_error(1).unwrap(),
+        ] {
+            assert!(AnyhowConversion::new().reason(reason).is_ok());
+        }
+        for reason in [
+            JsonRpcErrorReason::ParseError,
+            JsonRpcErrorReason::InvalidRequest,
+            JsonRpcErrorReason::MethodNotFound,
+            JsonRpcErrorReason::InvalidParams,
+            JsonRpcErrorReason::from(-32500),
+        ] {
+            let error = AnyhowConversion::new().reason(reason).unwrap_err();
+            assert_eq!(error.code(), i32::from(reason));
+        }
+    }
 }
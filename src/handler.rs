@@ -0,0 +1,342 @@
+//! Handler functions for [`JsonRpcRouter`](crate::JsonRpcRouter).
+//!
+//! Like axum handlers, their arguments are extracted from the call with
+//! [`FromJsonRpcCallParts`], except the last one which may consume the call with
+//! [`FromJsonRpcCall`], e.g. a [`JsonRpcExtractor`]. Their return value is turned into a
+//! response with [`IntoJsonRpcResponse`].
+//! ```rust
+//! use axum::extract::State;
+//! use axum_jrpc::error::JsonRpcError;
+//! use axum_jrpc::handler::Params;
+//! use axum_jrpc::{Id, JsonRpcRouter};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct AddArgs {
+//!     a: i32,
+//!     b: i32,
+//! }
+//!
+//! async fn add(State(offset): State<i32>, Params(p): Params<AddArgs>) -> Result<i32, JsonRpcError> {
+//!     Ok(p.a + p.b + offset)
+//! }
+//!
+//! async fn echo_id(id: Id) -> Result<String, JsonRpcError> {
+//!     Ok(id.to_string())
+//! }
+//!
+//! let rpc = JsonRpcRouter::with_state(1)
+//!     .route("add", add)
+//!     .route("echo_id", echo_id);
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use axum::extract::{FromRef, State};
+use axum::http::HeaderMap;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::JsonRpcError;
+use crate::{Id, JrpcResult, JsonRpcExtractor, JsonRpcResponse, JsonRpcVersion};
+
+#[derive(Debug, Clone)]
+/// A call dispatched by [`JsonRpcRouter`](crate::JsonRpcRouter): the validated request and
+/// the headers of the HTTP request carrying it, shared by all calls of a batch.
+pub struct JsonRpcCall {
+    pub request: JsonRpcExtractor,
+    pub headers: Arc<HeaderMap>,
+}
+
+/// Types that can be created from a borrowed call, to be used as any handler argument.
+pub trait FromJsonRpcCallParts<S>: Sized {
+    fn from_call_parts(call: &JsonRpcCall, state: &S) -> Result<Self, JsonRpcError>;
+}
+
+/// Types that can be created by consuming the call, to be used as the last handler argument.
+///
+/// Implemented for all [`FromJsonRpcCallParts`] types; `M` only tells the two apart.
+pub trait FromJsonRpcCall<S, M = private::ViaCall>: Sized {
+    fn from_call(call: JsonRpcCall, state: &S) -> Result<Self, JsonRpcError>;
+}
+
+mod private {
+    #[derive(Debug, Clone, Copy)]
+    pub enum ViaParts {}
+
+    #[derive(Debug, Clone, Copy)]
+    pub enum ViaCall {}
+}
+
+impl<S, T: FromJsonRpcCallParts<S>> FromJsonRpcCall<S, private::ViaParts> for T {
+    fn from_call(call: JsonRpcCall, state: &S) -> Result<Self, JsonRpcError> {
+        T::from_call_parts(&call, state)
+    }
+}
+
+pub use crate::params::Params;
+
+/// Extracts the deserialized params, or rejects the call with `Invalid params`.
+impl<S, T: DeserializeOwned> FromJsonRpcCallParts<S> for Params<T> {
+    fn from_call_parts(call: &JsonRpcCall, _state: &S) -> Result<Self, JsonRpcError> {
+        call.request.try_parse_params()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Extracts the method name.
+pub struct Method(pub String);
+
+impl<S> FromJsonRpcCallParts<S> for Method {
+    fn from_call_parts(call: &JsonRpcCall, _state: &S) -> Result<Self, JsonRpcError> {
+        Ok(Method(call.request.method.clone()))
+    }
+}
+
+/// Extracts the request id, [`Id::Null`] for notifications.
+impl<S> FromJsonRpcCallParts<S> for Id {
+    fn from_call_parts(call: &JsonRpcCall, _state: &S) -> Result<Self, JsonRpcError> {
+        Ok(call.request.get_answer_id())
+    }
+}
+
+/// Extracts the headers without copying them.
+impl<S> FromJsonRpcCallParts<S> for Arc<HeaderMap> {
+    fn from_call_parts(call: &JsonRpcCall, _state: &S) -> Result<Self, JsonRpcError> {
+        Ok(Arc::clone(&call.headers))
+    }
+}
+
+impl<S> FromJsonRpcCallParts<S> for HeaderMap {
+    fn from_call_parts(call: &JsonRpcCall, _state: &S) -> Result<Self, JsonRpcError> {
+        Ok(HeaderMap::clone(&call.headers))
+    }
+}
+
+impl<S, T: FromRef<S>> FromJsonRpcCallParts<S> for State<T> {
+    fn from_call_parts(_call: &JsonRpcCall, state: &S) -> Result<Self, JsonRpcError> {
+        Ok(State(T::from_ref(state)))
+    }
+}
+
+impl<S, T: FromJsonRpcCallParts<S>> FromJsonRpcCallParts<S> for Option<T> {
+    fn from_call_parts(call: &JsonRpcCall, state: &S) -> Result<Self, JsonRpcError> {
+        Ok(T::from_call_parts(call, state).ok())
+    }
+}
+
+impl<S, T: FromJsonRpcCallParts<S>> FromJsonRpcCallParts<S> for Result<T, JsonRpcError> {
+    fn from_call_parts(call: &JsonRpcCall, state: &S) -> Result<Self, JsonRpcError> {
+        Ok(T::from_call_parts(call, state))
+    }
+}
+
+/// Takes the request, params included, without copying it.
+impl<S> FromJsonRpcCall<S> for JsonRpcExtractor {
+    fn from_call(call: JsonRpcCall, _state: &S) -> Result<Self, JsonRpcError> {
+        Ok(call.request)
+    }
+}
+
+impl<S> FromJsonRpcCall<S> for JsonRpcCall {
+    fn from_call(call: JsonRpcCall, _state: &S) -> Result<Self, JsonRpcError> {
+        Ok(call)
+    }
+}
+
+impl<S, T: FromJsonRpcCall<S>> FromJsonRpcCall<S> for Option<T> {
+    fn from_call(call: JsonRpcCall, state: &S) -> Result<Self, JsonRpcError> {
+        Ok(T::from_call(call, state).ok())
+    }
+}
+
+impl<S, T: FromJsonRpcCall<S>> FromJsonRpcCall<S> for Result<T, JsonRpcError> {
+    fn from_call(call: JsonRpcCall, state: &S) -> Result<Self, JsonRpcError> {
+        Ok(T::from_call(call, state))
+    }
+}
+
+/// Return types of handlers.
+pub trait IntoJsonRpcResponse {
+    /// Builds the response to the call with the given `id`.
+    fn into_json_rpc_response(self, id: Id) -> JsonRpcResponse;
+}
+
+impl IntoJsonRpcResponse for JsonRpcResponse {
+    fn into_json_rpc_response(self, _id: Id) -> JsonRpcResponse {
+        self
+    }
+}
+
+impl IntoJsonRpcResponse for JrpcResult {
+    fn into_json_rpc_response(self, _id: Id) -> JsonRpcResponse {
+        self.unwrap_or_else(|e| e)
+    }
+}
+
+impl<T, E> IntoJsonRpcResponse for Result<T, E>
+where
+    T: Serialize,
+    E: Into<JsonRpcError>,
+{
+    fn into_json_rpc_response(self, id: Id) -> JsonRpcResponse {
+        match self {
+            Ok(result) => JsonRpcResponse::success(id, result),
+            Err(error) => JsonRpcResponse::error(id, error.into()),
+        }
+    }
+}
+
+/// Async functions usable as method handlers.
+///
+/// Implemented for functions taking up to 12 arguments, all [`FromJsonRpcCallParts`] but
+/// the last one which is [`FromJsonRpcCall`], and returning an [`IntoJsonRpcResponse`].
+pub trait JsonRpcHandler<T, S>: Clone + Send + Sync + Sized + 'static {
+    type Future: Future<Output = JsonRpcResponse> + Send + 'static;
+
+    fn call(self, call: JsonRpcCall, state: S) -> Self::Future;
+}
+
+/// Answers a call whose arguments could not be extracted.
+fn reject(id: Option<Id>, version: JsonRpcVersion, error: JsonRpcError) -> JsonRpcResponse {
+    match id {
+        Some(id) => JsonRpcResponse::error(id, error).with_version(version),
+        None => JsonRpcResponse::notification(),
+    }
+}
+
+impl<F, Fut, S, R> JsonRpcHandler<((),), S> for F
+where
+    F: FnOnce() -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = R> + Send,
+    R: IntoJsonRpcResponse,
+    S: Send + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = JsonRpcResponse> + Send>>;
+
+    fn call(self, call: JsonRpcCall, _state: S) -> Self::Future {
+        let id = call.request.get_answer_id();
+        Box::pin(async move { self().await.into_json_rpc_response(id) })
+    }
+}
+
+macro_rules! impl_handler {
+    ( [$($ty:ident),*], $last:ident ) => {
+        #[allow(non_snake_case, unused_variables)]
+        impl<F, Fut, S, R, M, $($ty,)* $last> JsonRpcHandler<(M, $($ty,)* $last,), S> for F
+        where
+            F: FnOnce($($ty,)* $last) -> Fut + Clone + Send + Sync + 'static,
+            Fut: Future<Output = R> + Send,
+            R: IntoJsonRpcResponse,
+            S: Send + 'static,
+            $( $ty: FromJsonRpcCallParts<S> + Send, )*
+            $last: FromJsonRpcCall<S, M> + Send,
+        {
+            type Future = Pin<Box<dyn Future<Output = JsonRpcResponse> + Send>>;
+
+            fn call(self, call: JsonRpcCall, state: S) -> Self::Future {
+                Box::pin(async move {
+                    let id = call.request.id.clone();
+                    let version = call.request.version();
+                    $(
+                        let $ty = match $ty::from_call_parts(&call, &state) {
+                            Ok(value) => value,
+                            Err(error) => return reject(id, version, error),
+                        };
+                    )*
+                    let $last = match $last::from_call(call, &state) {
+                        Ok(value) => value,
+                        Err(error) => return reject(id, version, error),
+                    };
+                    let answer_id = id.unwrap_or(Id::Null);
+                    self($($ty,)* $last).await.into_json_rpc_response(answer_id)
+                })
+            }
+        }
+    };
+}
+
+impl_handler!([], T1);
+impl_handler!([T1], T2);
+impl_handler!([T1, T2], T3);
+impl_handler!([T1, T2, T3], T4);
+impl_handler!([T1, T2, T3, T4], T5);
+impl_handler!([T1, T2, T3, T4, T5], T6);
+impl_handler!([T1, T2, T3, T4, T5, T6], T7);
+impl_handler!([T1, T2, T3, T4, T5, T6, T7], T8);
+impl_handler!([T1, T2, T3, T4, T5, T6, T7, T8], T9);
+impl_handler!([T1, T2, T3, T4, T5, T6, T7, T8, T9], T10);
+impl_handler!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10], T11);
+impl_handler!([T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11], T12);
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::client::JsonRpcTransport;
+    use crate::JsonRpcRouter;
+
+    type Seen = Arc<Mutex<Vec<usize>>>;
+
+    async fn describe(
+        State(seen): State<Seen>,
+        Method(method): Method,
+        headers: Arc<HeaderMap>,
+        req: JsonRpcExtractor,
+    ) -> JrpcResult {
+        seen.lock().unwrap().push(Arc::as_ptr(&headers) as usize);
+        let params: Vec<i32> = req.parse_params()?;
+        Ok(req.success(format!("{} {:?}", method, params)))
+    }
+
+    async fn send(router: &JsonRpcRouter<Seen>, request: Value) -> Value {
+        let response = router
+            .send(serde_json::to_vec(&request).unwrap())
+            .await
+            .unwrap();
+        serde_json::from_slice(&response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn last_argument_consumes_the_call() {
+        let seen = Seen::default();
+        let router = JsonRpcRouter::with_state(seen.clone()).route("describe", describe);
+        let response = send(
+            &router,
+            json!([
+                {"jsonrpc": "2.0", "method": "describe", "params": [1, 2], "id": 1},
+                {"jsonrpc": "2.0", "method": "describe", "params": [3], "id": 2},
+            ]),
+        )
+        .await;
+        assert_eq!(response[0]["result"], "describe [1, 2]");
+        assert_eq!(response[1]["result"], "describe [3]");
+        // The headers are shared by all calls of the batch.
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0], seen[1]);
+    }
+
+    #[tokio::test]
+    async fn rejections_answer_in_the_version_of_the_call() {
+        async fn add(
+            Params(p): Params<[i32; 2]>,
+            _req: JsonRpcExtractor,
+        ) -> Result<i32, JsonRpcError> {
+            Ok(p[0] + p[1])
+        }
+        let router = JsonRpcRouter::with_state(Seen::default()).route("add", add);
+        let response = send(
+            &router,
+            json!({"jsonrpc": "2.0", "method": "add", "params": [1], "id": 7}),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], -32602);
+        assert_eq!(response["id"], 7);
+    }
+}
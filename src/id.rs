@@ -0,0 +1,235 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+use serde_json::Number;
+
+/// JSON-RPC request [id](https://www.jsonrpc.org/specification#request_object).
+///
+/// Clients may identify a call with a string, a number or `null`; the same id is echoed
+/// back in the response. Numbers of requests are kept as written, so ids like
+/// `18446744073709551615` or `1e20` come back byte for byte.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Id {
+    Number(NumericId),
+    String(String),
+    Null,
+}
+
+/// Numeric [`Id`], stored as the raw JSON text sent by the client.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumericId(Box<str>);
+
+impl NumericId {
+    /// Returns the number as written in the request.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the number if it is an integer that fits into `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.0.parse().ok()
+    }
+
+    /// Returns the number if it is an integer that fits into `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.0.parse().ok()
+    }
+}
+
+impl std::fmt::Display for NumericId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+macro_rules! from_integer {
+    ( $($ty:ty),* ) => {
+        $(
+            impl From<$ty> for Id {
+                fn from(id: $ty) -> Self {
+                    Id::Number(NumericId(id.to_string().into()))
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl From<String> for Id {
+    fn from(id: String) -> Self {
+        Id::String(id)
+    }
+}
+
+impl From<&str> for Id {
+    fn from(id: &str) -> Self {
+        Id::String(id.to_owned())
+    }
+}
+
+impl std::fmt::Display for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Id::Number(id) => write!(f, "{}", id),
+            Id::String(id) => write!(f, "{:?}", id),
+            Id::Null => write!(f, "null"),
+        }
+    }
+}
+
+impl Serialize for Id {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            // Integers go through the data model, so formats other than JSON work too.
+            Id::Number(id) => match (id.as_u64(), id.as_i64()) {
+                (Some(n), _) if n.to_string() == id.as_str() => serializer.serialize_u64(n),
+                (_, Some(n)) if n.to_string() == id.as_str() => serializer.serialize_i64(n),
+                _ => RawValue::from_string(id.0.to_string())
+                    .map_err(serde::ser::Error::custom)?
+                    .serialize(serializer),
+            },
+            Id::String(id) => serializer.serialize_str(id),
+            Id::Null => serializer.serialize_unit(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Id {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(IdVisitor)
+    }
+}
+
+struct IdVisitor;
+
+impl<'de> Visitor<'de> for IdVisitor {
+    type Value = Id;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a string, a number or null")
+    }
+
+    fn visit_i64<E: de::Error>(self, id: i64) -> Result<Id, E> {
+        Ok(id.into())
+    }
+
+    fn visit_u64<E: de::Error>(self, id: u64) -> Result<Id, E> {
+        Ok(id.into())
+    }
+
+    fn visit_f64<E: de::Error>(self, id: f64) -> Result<Id, E> {
+        match Number::from_f64(id) {
+            Some(id) => Ok(Id::Number(NumericId(id.to_string().into()))),
+            None => Err(E::invalid_value(de::Unexpected::Float(id), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, id: &str) -> Result<Id, E> {
+        Ok(id.into())
+    }
+
+    fn visit_string<E: de::Error>(self, id: String) -> Result<Id, E> {
+        Ok(id.into())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Id, E> {
+        Ok(Id::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Id, E> {
+        Ok(Id::Null)
+    }
+}
+
+impl Id {
+    /// Reads an id from raw JSON text, keeping numbers as written.
+    pub(crate) fn from_raw(raw: &RawValue) -> Result<Self, String> {
+        parse_id(raw.get())
+    }
+}
+
+fn parse_id(raw: &str) -> Result<Id, String> {
+    match raw.as_bytes()[0] {
+        b'"' => serde_json::from_str(raw)
+            .map(Id::String)
+            .map_err(|e| e.to_string()),
+        b'n' => Ok(Id::Null),
+        b'-' | b'0'..=b'9' => Ok(Id::Number(NumericId(raw.into()))),
+        _ => Err(format!(
+            "invalid id `{}`, expected a string, a number or null",
+            raw
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::config::JsonRpcConfig;
+    use crate::{JsonRpcExtractor, JsonRpcResponse};
+
+    #[test]
+    fn keeps_numbers_of_requests_as_written() {
+        for raw in ["1", "-1", "-0", "18446744073709551615", "1e20", "1.50"] {
+            let request = format!(r#"{{"jsonrpc":"2.0","method":"a","id":{}}}"#, raw);
+            let call = JsonRpcExtractor::from_slice(request.as_bytes(), &JsonRpcConfig::default())
+                .unwrap();
+            assert_eq!(serde_json::to_string(&call.get_answer_id()).unwrap(), raw);
+        }
+    }
+
+    #[test]
+    fn serializes_integers_with_other_formats() {
+        let query = serde_urlencoded::to_string([("id", Id::from(1)), ("n", Id::from(-2))]);
+        assert_eq!(query.unwrap(), "id=1&n=-2");
+        let query = serde_urlencoded::to_string([("id", Id::from("a b"))]);
+        assert_eq!(query.unwrap(), "id=a+b");
+    }
+
+    #[test]
+    fn deserializes_from_values() {
+        let id: Id = serde_json::from_value(serde_json::json!(7)).unwrap();
+        assert_eq!(id, Id::from(7));
+        let id: Id = serde_json::from_value(serde_json::json!("a")).unwrap();
+        assert_eq!(id, Id::from("a"));
+        let id: Id = serde_json::from_value(serde_json::Value::Null).unwrap();
+        assert_eq!(id, Id::Null);
+        assert!(serde_json::from_value::<Id>(serde_json::json!({"a": 1})).is_err());
+    }
+
+    #[test]
+    fn deserializes_from_buffered_content() {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Responses {
+            One(JsonRpcResponse),
+            Many(Vec<JsonRpcResponse>),
+        }
+
+        let one = r#"{"jsonrpc":"2.0","result":1,"id":1}"#;
+        match serde_json::from_str(one).unwrap() {
+            Responses::One(response) => assert_eq!(response.id, Id::from(1)),
+            Responses::Many(_) => panic!("expected a single response"),
+        }
+
+        let many =
+            r#"[{"jsonrpc":"2.0","result":1,"id":"a"},{"jsonrpc":"2.0","result":2,"id":-2.5}]"#;
+        match serde_json::from_str(many).unwrap() {
+            Responses::Many(responses) => {
+                assert_eq!(responses[0].id, Id::from("a"));
+                assert_eq!(serde_json::to_string(&responses[1].id).unwrap(), "-2.5");
+            }
+            Responses::One(_) => panic!("expected a batch"),
+        }
+    }
+
+    #[test]
+    fn converts_integers() {
+        assert_eq!(Id::from(1), Id::from(1u64));
+        assert_eq!(Id::from(-1i8), Id::from(-1i64));
+        assert_eq!(Id::from(7u16).to_string(), "7");
+    }
+}
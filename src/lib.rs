@@ -2,7 +2,7 @@
     clippy::all,
     clippy::dbg_macro,
     clippy::todo,
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::enum_glob_use,
     clippy::mem_forget,
     clippy::unused_self,
@@ -11,9 +11,7 @@
     clippy::needless_borrow,
     clippy::match_wildcard_for_single_variants,
     clippy::if_let_mutex,
-    clippy::mismatched_target_os,
     clippy::await_holding_lock,
-    clippy::match_on_vec_items,
     clippy::imprecise_flops,
     clippy::suboptimal_flops,
     clippy::lossy_float_literal,
@@ -32,89 +30,227 @@
     nonstandard_style,
     missing_debug_implementations
 )]
-#![deny(unreachable_pub, private_in_public)]
+#![deny(unreachable_pub)]
 #![allow(elided_lifetimes_in_paths, clippy::type_complexity)]
 
-use axum::body::HttpBody;
+use axum::body::{Bytes, HttpBody};
 use axum::extract::FromRequest;
-use axum::http::Request;
+use axum::http::{header, HeaderValue, Request, StatusCode};
 use axum::response::{IntoResponse, Response};
-use axum::{BoxError, Json};
-use serde::de::DeserializeOwned;
+use axum::BoxError;
+use futures_util::future::join_all;
+use serde::de::Error as _;
+use serde::de::{IgnoredAny, MapAccess, Visitor};
+use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::value::RawValue;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::future::Future;
 
-use crate::error::{JsonRpcError, JsonRpcErrorReason};
+use crate::config::JsonRpcConfig;
+use crate::error::{JsonRpcError, MethodNotFound, ServerErrorCode};
 
+pub mod client;
+pub mod config;
 pub mod error;
+pub mod handler;
+mod id;
+mod params;
+mod query;
+pub mod router;
+
+pub use id::{Id, NumericId};
+pub use query::JsonRpcQueryExtractor;
+pub use router::JsonRpcRouter;
+
+pub use async_trait::async_trait;
+#[cfg(feature = "macros")]
+pub use axum_jrpc_macros::rpc;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+    pub use serde_json;
+}
 
 /// Hack until [try_trait_v2](https://github.com/rust-lang/rust/issues/84277) is not stabilized
 pub type JrpcResult = Result<JsonRpcResponse, JsonRpcResponse>;
 
-#[derive(Deserialize, Debug)]
-#[serde(deny_unknown_fields)]
+/// JSON-RPC protocol version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonRpcVersion {
+    /// [JSON-RPC 1.0](https://www.jsonrpc.org/specification_v1)
+    V1,
+    /// [JSON-RPC 2.0](https://www.jsonrpc.org/specification)
+    #[default]
+    V2,
+}
+
+#[derive(Debug)]
 pub struct JsonRpcRequest {
-    pub id: i64,
-    pub jsonrpc: String,
+    /// `None` if the request is a [notification](https://www.jsonrpc.org/specification#notification).
+    pub id: Option<Id>,
+    /// Missing in JSON-RPC 1.0 requests.
+    pub jsonrpc: Option<String>,
     pub method: String,
-    pub params: Value,
+    /// Raw JSON text of the params. Must be an array or an object if present.
+    pub params: Option<Box<RawValue>>,
+    /// Any other members, rejected unless the extractor is configured as lenient.
+    pub extra: Map<String, Value>,
 }
 
-#[derive(Debug)]
+impl<'de> Deserialize<'de> for JsonRpcRequest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RequestVisitor;
+
+        impl<'de> Visitor<'de> for RequestVisitor {
+            type Value = JsonRpcRequest;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("struct JsonRpcRequest")
+            }
+
+            // Written by hand because `#[serde(flatten)]` buffers the members and
+            // `RawValue` can't be deserialized from a buffer.
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut id = None;
+                let mut jsonrpc = None;
+                let mut method = None;
+                let mut params = None;
+                let mut extra = Map::new();
+                while let Some(key) = map.next_key::<Cow<'de, str>>()? {
+                    match key.as_ref() {
+                        "id" if id.is_none() => {
+                            let raw = map.next_value::<Box<RawValue>>()?;
+                            id = Some(Id::from_raw(&raw).map_err(A::Error::custom)?);
+                        }
+                        "jsonrpc" if jsonrpc.is_none() => jsonrpc = Some(map.next_value()?),
+                        "method" if method.is_none() => method = Some(map.next_value()?),
+                        "params" if params.is_none() => params = Some(map.next_value()?),
+                        "id" | "jsonrpc" | "method" | "params" => {
+                            return Err(A::Error::custom(format!("duplicate field `{}`", key)))
+                        }
+                        _ => {
+                            extra.insert(key.into_owned(), map.next_value()?);
+                        }
+                    }
+                }
+                Ok(JsonRpcRequest {
+                    id,
+                    jsonrpc,
+                    method: method.ok_or_else(|| A::Error::missing_field("method"))?,
+                    params,
+                    extra,
+                })
+            }
+        }
+
+        deserializer.deserialize_map(RequestVisitor)
+    }
+}
+
+#[derive(Debug, Clone)]
 /// Parses a JSON-RPC request, and returns the request ID, the method name, and the parameters.
 /// If the request is invalid, returns an error.
 /// ```rust
-/// use axum_jrpc::{JrpcResult, JsonRpcExtractor, JsonRpcResponse};
+/// use axum_jrpc::{JrpcResult, JsonRpcExtractor};
 ///
 /// fn router(req: JsonRpcExtractor) -> JrpcResult {
-///   let req_id = req.get_answer_id()?;
 ///   let method = req.method();
 ///   match method {
 ///     "add" => {
 ///        let params: [i32;2] = req.parse_params()?;
-///        return Ok(JsonRpcResponse::success(req_id, params[0] + params[1]));
+///        return Ok(req.success(params[0] + params[1]));
 ///     }
 ///     m =>  Ok(req.method_not_found(m))
 ///   }
 /// }
 /// ```
 pub struct JsonRpcExtractor {
-    pub parsed: Value,
+    /// Raw JSON text of the call parameters, an array or an object.
+    /// `None` if `params` was omitted.
+    pub parsed: Option<Box<RawValue>>,
     pub method: String,
-    pub id: i64,
+    /// `None` if the call is a notification.
+    pub id: Option<Id>,
+    /// Unknown top-level members, only kept in lenient mode.
+    pub extra: Map<String, Value>,
+    pub version: JsonRpcVersion,
 }
 
 impl JsonRpcExtractor {
-    pub fn get_answer_id(&self) -> i64 {
-        self.id
+    /// Returns the request ID, or [`Id::Null`] for notifications.
+    pub fn get_answer_id(&self) -> Id {
+        self.id.clone().unwrap_or(Id::Null)
+    }
+
+    /// Returns `true` if the call has no `id` and must not be answered.
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
     }
 
-    pub fn parse_params<T: DeserializeOwned>(self) -> Result<T, JsonRpcResponse> {
-        let value = serde_json::from_value(self.parsed);
+    /// Deserializes the parameters straight from the request text, so `T` may borrow
+    /// from it, e.g. with `&str` fields.
+    /// Omitted `params` are treated as empty: `null`, `[]` and `{}` are tried in that order.
+    pub fn parse_params<'a, T: Deserialize<'a>>(&'a self) -> Result<T, JsonRpcResponse> {
+        self.try_parse_params().map_err(|error| self.error(error))
+    }
+
+    pub(crate) fn try_parse_params<'a, T: Deserialize<'a>>(&'a self) -> Result<T, JsonRpcError> {
+        let value = match &self.parsed {
+            None => serde_json::from_str("null")
+                .or_else(|_| serde_json::from_str("[]"))
+                .or_else(|e| serde_json::from_str("{}").map_err(|_| e)),
+            Some(params) => serde_json::from_str(params.get()),
+        };
         match value {
             Ok(v) => Ok(v),
-            Err(e) => {
-                let error = JsonRpcError::new(
-                    JsonRpcErrorReason::InvalidParams,
-                    e.to_string(),
-                    Value::Null,
-                );
-                Err(JsonRpcResponse::error(self.id, error))
-            }
+            Err(e) => Err(JsonRpcError::invalid_params(e.to_string())),
         }
     }
 
+    /// Returns the raw JSON text of the parameters, `None` if they were omitted.
+    pub fn params(&self) -> Option<&RawValue> {
+        self.parsed.as_deref()
+    }
+
     pub fn method(&self) -> &str {
         &self.method
     }
 
+    /// Returns the protocol version of the call.
+    /// Only [`JsonRpcVersion::V1`] if [`JsonRpcConfig::accept_v1`] is enabled.
+    pub fn version(&self) -> JsonRpcVersion {
+        self.version
+    }
+
+    /// Returns the unknown top-level members of the request.
+    /// Always empty unless [`JsonRpcConfig::strict`] is disabled.
+    pub fn extra(&self) -> &Map<String, Value> {
+        &self.extra
+    }
+
     pub fn method_not_found(&self, method: &str) -> JsonRpcResponse {
-        let error = JsonRpcError::new(
-            JsonRpcErrorReason::MethodNotFound,
-            format!("Method `{}` not found", method),
-            Value::Null,
-        );
-        JsonRpcResponse::error(self.id, error)
+        self.error(MethodNotFound::new(method).into())
+    }
+
+    /// Answers the call with `result`, in the protocol version of the call.
+    /// Returns an empty response if the call is a notification.
+    pub fn success<T: Serialize>(&self, result: T) -> JsonRpcResponse {
+        match &self.id {
+            Some(id) => JsonRpcResponse::success(id.clone(), result).with_version(self.version),
+            None => JsonRpcResponse::notification(),
+        }
+    }
+
+    /// Answers the call with `error`, in the protocol version of the call.
+    /// Returns an empty response if the call is a notification.
+    pub fn error(&self, error: JsonRpcError) -> JsonRpcResponse {
+        match &self.id {
+            Some(id) => JsonRpcResponse::error(id.clone(), error).with_version(self.version),
+            None => JsonRpcResponse::notification(),
+        }
     }
 }
 
@@ -129,68 +265,383 @@ where
     type Rejection = JsonRpcResponse;
 
     async fn from_request(req: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
-        let json = Json::from_request(req, state).await;
-        let parsed: JsonRpcRequest = match json {
-            Ok(a) => a.0,
+        let config = JsonRpcConfig::from_extensions(req.extensions());
+        let body = read_body(req, state, &config).await?;
+        JsonRpcExtractor::from_slice(&body, &config)
+    }
+}
+
+/// Reads the body, rejecting unsupported content types.
+async fn read_body<S, B>(
+    req: Request<B>,
+    state: &S,
+    config: &JsonRpcConfig,
+) -> Result<Bytes, JsonRpcResponse>
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    S: Send + Sync,
+{
+    if !config.accepts_content_type(req.headers()) {
+        let expected = config
+            .accepted_content_types()
+            .map(|content_type| format!("`{}`", content_type))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(JsonRpcResponse::error(
+            Id::Null,
+            JsonRpcError::server_error(
+                ServerErrorCode::UNSUPPORTED_CONTENT_TYPE,
+                format!("Expected request with `Content-Type` one of {}", expected),
+            ),
+        ));
+    }
+    match Bytes::from_request(req, state).await {
+        Ok(bytes) => Ok(bytes),
+        Err(e) => Err(invalid_request(e.body_text())),
+    }
+}
+
+fn parse_error(error: serde_json::Error) -> JsonRpcResponse {
+    JsonRpcResponse::error(
+        Id::Null,
+        JsonRpcError::parse_error(format!(
+            "Failed to parse the request body as JSON: {}",
+            error
+        )),
+    )
+}
+
+impl JsonRpcExtractor {
+    /// Parses and validates a single request object, classifying failures as a parse error
+    /// or an invalid request.
+    pub(crate) fn from_slice(json: &[u8], config: &JsonRpcConfig) -> Result<Self, JsonRpcResponse> {
+        let parsed: JsonRpcRequest = match serde_json::from_slice(json) {
+            Ok(parsed) => parsed,
+            Err(e) if e.is_syntax() || e.is_eof() => return Err(parse_error(e)),
             Err(e) => {
-                return Err(JsonRpcResponse {
-                    id: 0,
-                    jsonrpc: "2.0".to_owned(),
-                    result: JsonRpcAnswer::Error(JsonRpcError::new(
-                        JsonRpcErrorReason::InvalidRequest,
-                        e.to_string(),
-                        Value::Null,
-                    )),
-                })
+                // The shape is checked while parsing, so the rest may still be malformed.
+                if let Err(e) = serde_json::from_slice::<IgnoredAny>(json) {
+                    return Err(parse_error(e));
+                }
+                // Echo the id back when it can be recovered from a malformed request.
+                // Only objects have one; `IdOnly` would also accept an array like `[1]`.
+                #[derive(Deserialize)]
+                struct IdOnly {
+                    id: Option<Box<RawValue>>,
+                }
+                let is_object = json.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{');
+                let id = is_object
+                    .then(|| serde_json::from_slice::<IdOnly>(json).ok())
+                    .flatten()
+                    .and_then(|request| request.id)
+                    .and_then(|id| Id::from_raw(&id).ok())
+                    .unwrap_or(Id::Null);
+                return Err(JsonRpcResponse::error(
+                    id,
+                    JsonRpcError::invalid_request(e.to_string()),
+                ));
             }
         };
-        if parsed.jsonrpc != "2.0" {
-            return Err(JsonRpcResponse {
-                id: parsed.id,
-                jsonrpc: "2.0".to_owned(),
-                result: JsonRpcAnswer::Error(JsonRpcError::new(
-                    JsonRpcErrorReason::InvalidRequest,
-                    "Invalid jsonrpc version".to_owned(),
-                    Value::Null,
-                )),
-            });
+        let version = match parsed.jsonrpc.as_deref() {
+            Some("2.0") => JsonRpcVersion::V2,
+            None if config.accepts_v1() => JsonRpcVersion::V1,
+            _ => {
+                return Err(JsonRpcResponse::error(
+                    parsed.id.unwrap_or(Id::Null),
+                    JsonRpcError::invalid_request("Invalid jsonrpc version"),
+                ));
+            }
+        };
+        let reject = |message: String| {
+            JsonRpcResponse::error(
+                parsed.id.clone().unwrap_or(Id::Null),
+                JsonRpcError::invalid_request(message),
+            )
+            .with_version(version)
+        };
+        if config.is_strict() {
+            if let Some(field) = parsed.extra.keys().next() {
+                return Err(reject(format!(
+                    "unknown field `{}`, expected one of `id`, `jsonrpc`, `method`, `params`",
+                    field
+                )));
+            }
+        }
+        let structure = parsed
+            .params
+            .as_ref()
+            .map(|params| params.get().as_bytes()[0]);
+        match (version, structure) {
+            (_, None)
+            | (JsonRpcVersion::V1, Some(b'['))
+            | (JsonRpcVersion::V2, Some(b'[' | b'{')) => {}
+            (JsonRpcVersion::V1, Some(_)) => {
+                return Err(reject("`params` must be an array".to_owned()));
+            }
+            (JsonRpcVersion::V2, Some(_)) => {
+                return Err(reject("`params` must be an array or an object".to_owned()));
+            }
         }
+        let id = match (version, parsed.id) {
+            // JSON-RPC 1.0 notifications have a `null` id.
+            (JsonRpcVersion::V1, Some(Id::Null)) => None,
+            (_, id) => id,
+        };
         Ok(Self {
             parsed: parsed.params,
             method: parsed.method,
-            id: parsed.id,
+            id,
+            extra: parsed.extra,
+            version,
         })
     }
 }
 
-#[derive(Serialize, Debug, Deserialize)]
+pub(crate) fn invalid_request(message: String) -> JsonRpcResponse {
+    JsonRpcResponse::error(Id::Null, JsonRpcError::invalid_request(message))
+}
+
+#[derive(Debug)]
+/// Parses a single JSON-RPC request or a [batch](https://www.jsonrpc.org/specification#batch)
+/// of them. Every call in a batch is validated the same way as by [`JsonRpcExtractor`].
+/// ```rust
+/// use axum_jrpc::{JsonRpcBatchExtractor, JsonRpcBatchResponse, JsonRpcExtractor};
+///
+/// async fn handler(batch: JsonRpcBatchExtractor) -> JsonRpcBatchResponse {
+///   batch.handle(|req: JsonRpcExtractor| async move {
+///     match req.method() {
+///       "add" => {
+///         let params: [i32;2] = req.parse_params()?;
+///         Ok(req.success(params[0] + params[1]))
+///       }
+///       m => Ok(req.method_not_found(m)),
+///     }
+///   }).await
+/// }
+/// ```
+pub struct JsonRpcBatchExtractor {
+    calls: Vec<Result<JsonRpcExtractor, JsonRpcResponse>>,
+    batch: bool,
+}
+
+impl JsonRpcBatchExtractor {
+    /// Returns `true` if the request body was an array of calls.
+    pub fn is_batch(&self) -> bool {
+        self.batch
+    }
+
+    /// Returns the validated calls, or the error responses for the invalid ones.
+    pub fn into_calls(self) -> Vec<Result<JsonRpcExtractor, JsonRpcResponse>> {
+        self.calls
+    }
+
+    /// Runs `handler` for every valid call concurrently and collects the responses.
+    pub async fn handle<F, Fut>(self, handler: F) -> JsonRpcBatchResponse
+    where
+        F: Fn(JsonRpcExtractor) -> Fut,
+        Fut: Future<Output = JrpcResult>,
+    {
+        let responses = join_all(self.calls.into_iter().map(|call| {
+            let call = call.map(|call| (call.is_notification(), handler(call)));
+            async move {
+                match call {
+                    Ok((notification, future)) => {
+                        let response = future.await.unwrap_or_else(|e| e);
+                        // Notifications are never answered, whatever the handler returns.
+                        if notification {
+                            JsonRpcResponse::notification()
+                        } else {
+                            response
+                        }
+                    }
+                    Err(e) => e,
+                }
+            }
+        }))
+        .await;
+
+        if self.batch {
+            JsonRpcBatchResponse::Batch(responses)
+        } else {
+            JsonRpcBatchResponse::Single(responses.into_iter().next().expect("single call"))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, B> FromRequest<S, B> for JsonRpcBatchExtractor
+where
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    S: Send + Sync,
+{
+    type Rejection = JsonRpcResponse;
+
+    async fn from_request(req: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        let config = JsonRpcConfig::from_extensions(req.extensions());
+        let body = read_body(req, state, &config).await?;
+        let first = body.iter().find(|byte| !byte.is_ascii_whitespace());
+        if first != Some(&b'[') {
+            return Ok(Self {
+                calls: vec![Ok(JsonRpcExtractor::from_slice(&body, &config)?)],
+                batch: false,
+            });
+        }
+        let calls: Vec<&RawValue> = serde_json::from_slice(&body).map_err(parse_error)?;
+        if calls.is_empty() {
+            return Err(invalid_request("Empty batch".to_owned()));
+        }
+        Ok(Self {
+            calls: calls
+                .into_iter()
+                .map(|call| JsonRpcExtractor::from_slice(call.get().as_bytes(), &config))
+                .collect(),
+            batch: true,
+        })
+    }
+}
+
+#[derive(Debug)]
 /// A JSON-RPC response.
+/// Serialized with either a `result` or an `error` member, never both.
+/// JSON-RPC 1.0 responses have no `jsonrpc` member and carry both, one of them `null`.
+///
+/// Written with `Content-Type: application/json`. Only under a
+/// [`JsonRpcLayer`](config::JsonRpcLayer) is the media type of the request echoed instead.
 pub struct JsonRpcResponse {
-    jsonrpc: String,
+    version: JsonRpcVersion,
     pub result: JsonRpcAnswer,
     /// The request ID.
-    id: i64,
+    id: Id,
+    notification: bool,
+}
+
+impl Serialize for JsonRpcResponse {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("JsonRpcResponse", 3)?;
+        match self.version {
+            JsonRpcVersion::V2 => {
+                state.serialize_field("jsonrpc", "2.0")?;
+                match &self.result {
+                    JsonRpcAnswer::Result(result) => state.serialize_field("result", result)?,
+                    JsonRpcAnswer::Error(error) => state.serialize_field("error", error)?,
+                }
+            }
+            JsonRpcVersion::V1 => match &self.result {
+                JsonRpcAnswer::Result(result) => {
+                    state.serialize_field("result", result)?;
+                    state.serialize_field("error", &Value::Null)?;
+                }
+                JsonRpcAnswer::Error(error) => {
+                    state.serialize_field("result", &Value::Null)?;
+                    state.serialize_field("error", error)?;
+                }
+            },
+        }
+        state.serialize_field("id", &self.id)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonRpcResponse {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Helper {
+            #[serde(default)]
+            jsonrpc: Option<String>,
+            #[serde(default, deserialize_with = "deserialize_some_value")]
+            result: Option<Value>,
+            #[serde(default)]
+            error: Option<JsonRpcError>,
+            id: Id,
+        }
+
+        let helper = Helper::deserialize(deserializer)?;
+        let version = match helper.jsonrpc.as_deref() {
+            Some("2.0") => JsonRpcVersion::V2,
+            Some(version) => {
+                return Err(D::Error::custom(format!(
+                    "invalid jsonrpc version `{}`",
+                    version
+                )))
+            }
+            None => JsonRpcVersion::V1,
+        };
+        let result = match (helper.result, helper.error) {
+            // JSON-RPC 1.0 sends the unused member as `null`
+            (_, Some(error)) if version == JsonRpcVersion::V1 => JsonRpcAnswer::Error(error),
+            (Some(result), None) => JsonRpcAnswer::Result(result),
+            (None, Some(error)) => JsonRpcAnswer::Error(error),
+            (Some(_), Some(_)) => {
+                return Err(D::Error::custom(
+                    "response must not contain both `result` and `error`",
+                ))
+            }
+            (None, None) => return Err(D::Error::custom("missing field `result` or `error`")),
+        };
+        Ok(JsonRpcResponse {
+            version,
+            result,
+            id: helper.id,
+            notification: false,
+        })
+    }
+}
+
+/// Keeps an explicit `"result": null` apart from a missing `result`.
+fn deserialize_some_value<'de, D>(deserializer: D) -> Result<Option<Value>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Value::deserialize(deserializer).map(Some)
 }
 
 impl JsonRpcResponse {
-    fn new(id: i64, result: JsonRpcAnswer) -> Self {
+    fn new(id: Id, result: JsonRpcAnswer) -> Self {
         Self {
-            jsonrpc: "2.0".to_owned(),
+            version: JsonRpcVersion::V2,
             result,
             id,
+            notification: false,
         }
     }
+
+    /// Returns an empty response for a notification.
+    /// It is written as `204 No Content` without a JSON-RPC response object.
+    pub fn notification() -> Self {
+        Self {
+            notification: true,
+            ..JsonRpcResponse::new(Id::Null, JsonRpcAnswer::Result(Value::Null))
+        }
+    }
+
+    /// Sets the protocol version the response is serialized with.
+    /// Pass [`JsonRpcExtractor::version`] to answer JSON-RPC 1.0 calls in kind, as
+    /// [`JsonRpcExtractor::success`] and [`JsonRpcExtractor::error`] do.
+    pub fn with_version(mut self, version: JsonRpcVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn version(&self) -> JsonRpcVersion {
+        self.version
+    }
+
+    /// Returns `true` if this is an empty response for a notification.
+    pub fn is_notification(&self) -> bool {
+        self.notification
+    }
+
     /// Returns a response with the given result
     /// Returns JsonRpcError if the `result` is invalid input for [`serde_json::to_value`]
-    pub fn success<T: Serialize>(id: i64, result: T) -> Self {
+    pub fn success<T: Serialize>(id: Id, result: T) -> Self {
         let result = match serde_json::to_value(result) {
             Ok(v) => v,
             Err(e) => {
-                let err = JsonRpcError::new(
-                    JsonRpcErrorReason::InternalError,
-                    e.to_string(),
-                    Value::Null,
-                );
+                let err = JsonRpcError::internal_error(e.to_string());
                 return JsonRpcResponse::error(id, err);
             }
         };
@@ -198,21 +649,336 @@ impl JsonRpcResponse {
         JsonRpcResponse::new(id, JsonRpcAnswer::Result(result))
     }
 
-    pub fn error(id: i64, error: JsonRpcError) -> Self {
+    pub fn error(id: Id, error: JsonRpcError) -> Self {
         JsonRpcResponse::new(id, JsonRpcAnswer::Error(error))
     }
 }
 
 impl IntoResponse for JsonRpcResponse {
     fn into_response(self) -> Response {
-        Json(self).into_response()
+        if self.notification {
+            return StatusCode::NO_CONTENT.into_response();
+        }
+        let (mut res, body) = json_body(&self);
+        let body = match body {
+            Some(body) => body,
+            None => return res,
+        };
+        if let JsonRpcAnswer::Error(error) = &self.result {
+            // Lets `JsonRpcLayer` pick the status code and redact the error.
+            res.extensions_mut().insert(error.error_reason());
+            res.extensions_mut().insert(JsonRpcErrors {
+                body,
+                batch: false,
+                responses: vec![(0, self)],
+            });
+        }
+        res
+    }
+}
+
+/// Marks responses carrying a JSON-RPC body for `JsonRpcLayer`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct JsonRpcBody;
+
+/// Error responses of a JSON-RPC body with their index in a batch, kept for `JsonRpcLayer`
+/// to redact.
+#[derive(Debug)]
+pub(crate) struct JsonRpcErrors {
+    pub(crate) body: Bytes,
+    pub(crate) batch: bool,
+    pub(crate) responses: Vec<(usize, JsonRpcResponse)>,
+}
+
+/// Writes `value` as a JSON response, also returning the body unless serialization failed.
+fn json_body<T: Serialize>(value: &T) -> (Response, Option<Bytes>) {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => Bytes::from(body),
+        Err(e) => {
+            let res = (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+            return (res, None);
+        }
+    };
+    let content_type = HeaderValue::from_static("application/json");
+    let mut res = ([(header::CONTENT_TYPE, content_type)], body.clone()).into_response();
+    res.extensions_mut().insert(JsonRpcBody);
+    (res, Some(body))
+}
+
+#[derive(Debug)]
+/// Responses to a single call or to a batch.
+/// Responses to notifications are left out; if nothing remains, `204 No Content` is written.
+pub enum JsonRpcBatchResponse {
+    Single(JsonRpcResponse),
+    Batch(Vec<JsonRpcResponse>),
+}
+
+impl IntoResponse for JsonRpcBatchResponse {
+    fn into_response(self) -> Response {
+        match self {
+            JsonRpcBatchResponse::Single(response) => response.into_response(),
+            JsonRpcBatchResponse::Batch(responses) => {
+                let responses: Vec<_> = responses
+                    .into_iter()
+                    .filter(|response| !response.is_notification())
+                    .collect();
+                if responses.is_empty() {
+                    return StatusCode::NO_CONTENT.into_response();
+                }
+                let (mut res, body) = json_body(&responses);
+                let body = match body {
+                    Some(body) => body,
+                    None => return res,
+                };
+                let errors: Vec<_> = responses
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, response)| matches!(response.result, JsonRpcAnswer::Error(_)))
+                    .collect();
+                if !errors.is_empty() {
+                    res.extensions_mut().insert(JsonRpcErrors {
+                        body,
+                        batch: true,
+                        responses: errors,
+                    });
+                }
+                res
+            }
+        }
     }
 }
 
 #[derive(Serialize, Debug, Deserialize)]
-#[serde(untagged)]
+#[serde(rename_all = "lowercase")]
 /// JsonRpc [response object](https://www.jsonrpc.org/specification#response_object)
+/// member. On its own it is written as the single member it stands for, `{"result": ..}` or
+/// `{"error": ..}`, so both directions agree with [`JsonRpcResponse`].
 pub enum JsonRpcAnswer {
     Result(Value),
     Error(JsonRpcError),
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{Body, HttpBody};
+    use axum::extract::FromRequest;
+    use axum::http::{header, Request, StatusCode};
+    use axum::response::{IntoResponse, Response};
+
+    use super::*;
+
+    async fn add(req: JsonRpcExtractor) -> JrpcResult {
+        let params: [i32; 2] = req.parse_params()?;
+        Ok(JsonRpcResponse::success(
+            req.get_answer_id(),
+            params[0] + params[1],
+        ))
+    }
+
+    async fn handle_batch(body: &'static str) -> Response {
+        let request = Request::post("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        let batch = JsonRpcBatchExtractor::from_request(request, &())
+            .await
+            .unwrap();
+        batch.handle(add).await.into_response()
+    }
+
+    async fn body_json(response: Response) -> Value {
+        let mut body = response.into_body();
+        let mut bytes = Vec::new();
+        while let Some(chunk) = body.data().await {
+            bytes.extend_from_slice(&chunk.unwrap());
+        }
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn reject(json: &str) -> (i32, Id) {
+        let response =
+            JsonRpcExtractor::from_slice(json.as_bytes(), &JsonRpcConfig::default()).unwrap_err();
+        match response.result {
+            JsonRpcAnswer::Error(ref error) => (error.code(), response.id),
+            JsonRpcAnswer::Result(_) => panic!("expected an error for {}", json),
+        }
+    }
+
+    #[test]
+    fn malformed_json_is_a_parse_error() {
+        let parse_error = (-32700, Id::Null);
+        assert_eq!(reject(r#"{"jsonrpc":"2.0","method":"#), parse_error);
+        assert_eq!(reject(r#"{"jsonrpc":"2.0" "method":"a"}"#), parse_error);
+        assert_eq!(reject(""), parse_error);
+        // Fails the shape check first, but is not valid JSON either.
+        assert_eq!(
+            reject(r#"{"jsonrpc":"2.0","method":1,"id":7,]"#),
+            parse_error
+        );
+    }
+
+    #[test]
+    fn wrong_shape_is_an_invalid_request() {
+        assert_eq!(
+            reject(r#"{"jsonrpc":"2.0","method":1,"id":7}"#),
+            (-32600, Id::from(7))
+        );
+        assert_eq!(
+            reject(r#"{"jsonrpc":"2.0","params":[],"id":"a"}"#),
+            (-32600, Id::from("a"))
+        );
+        assert_eq!(
+            reject(r#"{"jsonrpc":"2.0","method":1,"id":[7]}"#),
+            (-32600, Id::Null)
+        );
+        assert_eq!(reject("[1]"), (-32600, Id::Null));
+        assert_eq!(
+            reject(r#"{"jsonrpc":"1.0","method":"a","id":7}"#),
+            (-32600, Id::from(7))
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_content_types() {
+        let body = r#"{"jsonrpc":"2.0","method":"a","id":1}"#;
+        for content_type in [Some("text/plain"), Some("application/xml"), None] {
+            let mut request = Request::post("/");
+            if let Some(content_type) = content_type {
+                request = request.header(header::CONTENT_TYPE, content_type);
+            }
+            let request = request.body(Body::from(body)).unwrap();
+            let response = JsonRpcExtractor::from_request(request, &())
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(
+                    response.result,
+                    JsonRpcAnswer::Error(ref e)
+                        if e.code() == ServerErrorCode::UNSUPPORTED_CONTENT_TYPE.code()
+                ),
+                "{:?}",
+                content_type
+            );
+        }
+
+        let request = Request::post("/")
+            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+            .body(Body::from(body))
+            .unwrap();
+        let request = JsonRpcExtractor::from_request(request, &()).await.unwrap();
+        assert_eq!(request.method(), "a");
+    }
+
+    fn parse_response(json: &str) -> Result<JsonRpcResponse, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn responses_have_result_or_error() {
+        let response = parse_response(r#"{"jsonrpc":"2.0","result":null,"id":1}"#).unwrap();
+        assert!(matches!(
+            response.result,
+            JsonRpcAnswer::Result(Value::Null)
+        ));
+        assert_eq!(response.version(), JsonRpcVersion::V2);
+
+        let response =
+            parse_response(r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"x"},"id":1}"#)
+                .unwrap();
+        assert!(matches!(&response.result, JsonRpcAnswer::Error(e) if e.code() == -32601));
+
+        let error = parse_response(
+            r#"{"jsonrpc":"2.0","result":1,"error":{"code":1,"message":"x"},"id":1}"#,
+        )
+        .unwrap_err();
+        assert!(error.contains("must not contain both"), "{}", error);
+        let error = parse_response(r#"{"jsonrpc":"2.0","id":1}"#).unwrap_err();
+        assert!(
+            error.contains("missing field `result` or `error`"),
+            "{}",
+            error
+        );
+        assert!(parse_response(r#"{"jsonrpc":"1.5","result":1,"id":1}"#).is_err());
+    }
+
+    #[test]
+    fn v1_responses_carry_both_members() {
+        let response = parse_response(r#"{"result":3,"error":null,"id":1}"#).unwrap();
+        assert_eq!(response.version(), JsonRpcVersion::V1);
+        assert!(matches!(response.result, JsonRpcAnswer::Result(ref v) if v == 3));
+
+        let json = r#"{"result":null,"error":{"code":-32603,"message":"x","data":null},"id":1}"#;
+        let response = parse_response(json).unwrap();
+        assert!(matches!(response.result, JsonRpcAnswer::Error(_)));
+        assert_eq!(serde_json::to_string(&response).unwrap(), json);
+    }
+
+    #[test]
+    fn answers_round_trip() {
+        let answer: JsonRpcAnswer =
+            serde_json::from_str(r#"{"error":{"code":-32601,"message":"x"}}"#).unwrap();
+        assert!(matches!(&answer, JsonRpcAnswer::Error(e) if e.code() == -32601));
+        assert_eq!(
+            serde_json::to_value(&answer).unwrap(),
+            serde_json::json!({"error":{"code":-32601,"message":"x","data":null}})
+        );
+
+        let answer: JsonRpcAnswer = serde_json::from_str(r#"{"result":{"code":1}}"#).unwrap();
+        assert!(matches!(answer, JsonRpcAnswer::Result(_)));
+        assert_eq!(
+            serde_json::to_value(&answer).unwrap(),
+            serde_json::json!({"result":{"code":1}})
+        );
+    }
+
+    #[test]
+    fn answers_in_the_version_of_the_call() {
+        let config = JsonRpcConfig::default().accept_v1(true);
+        let call = JsonRpcExtractor::from_slice(br#"{"method":"add","params":[],"id":1}"#, &config)
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(call.success(3)).unwrap(),
+            serde_json::json!({"result":3,"error":null,"id":1})
+        );
+        let error = call.error(JsonRpcError::invalid_params("bad"));
+        assert_eq!(error.version(), JsonRpcVersion::V1);
+
+        let call =
+            JsonRpcExtractor::from_slice(br#"{"jsonrpc":"2.0","method":"add"}"#, &config).unwrap();
+        assert!(call.success(3).is_notification());
+    }
+
+    #[tokio::test]
+    async fn batch_leaves_out_notifications() {
+        let response = handle_batch(
+            r#"[
+                {"jsonrpc":"2.0","method":"add","params":[1,2],"id":1},
+                {"jsonrpc":"2.0","method":"add","params":[1,2]}
+            ]"#,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_json(response).await,
+            serde_json::json!([{"jsonrpc":"2.0","result":3,"id":1}])
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_of_notifications_has_no_content() {
+        let response = handle_batch(
+            r#"[
+                {"jsonrpc":"2.0","method":"add","params":[1,2]},
+                {"jsonrpc":"2.0","method":"add","params":[3,4]}
+            ]"#,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn single_notification_has_no_content() {
+        let response = handle_batch(r#"{"jsonrpc":"2.0","method":"add","params":[1,2]}"#).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+}
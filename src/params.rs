@@ -0,0 +1,633 @@
+use std::fmt;
+
+use std::marker::PhantomData;
+
+use serde::de::value::{BorrowedStrDeserializer, MapAccessDeserializer, MapDeserializer};
+use serde::de::value::{SeqDeserializer, StringDeserializer};
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, IntoDeserializer, MapAccess};
+use serde::de::{SeqAccess, Visitor};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Params accepted both as a positional array and as a named object.
+///
+/// Array elements are mapped to struct fields in declaration order, and the values of an
+/// object are mapped to tuple or array elements in document order. Only the top level is
+/// converted, nested values must match their type. Works with any deserializer, e.g.
+/// `serde_json::from_value`; strings of positional struct params are borrowed when the input
+/// allows it, as with [`JsonRpcExtractor::parse_params`](crate::JsonRpcExtractor::parse_params).
+/// ```rust
+/// use axum_jrpc::handler::Params;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Test {
+///     a: i32,
+///     b: Option<i32>,
+/// }
+///
+/// let Params(test): Params<Test> = serde_json::from_str("[1]").unwrap();
+/// assert_eq!((test.a, test.b), (1, None));
+///
+/// let Params(pair): Params<[i32; 2]> = serde_json::from_str(r#"{"a":1,"b":2}"#).unwrap();
+/// assert_eq!(pair, [1, 2]);
+/// ```
+pub struct Params<T>(pub T);
+
+impl<T> Params<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Params<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(AnyShape(deserializer)).map(Params)
+    }
+}
+
+/// Forwards to the inner deserializer, converting the shape for structs and tuples.
+struct AnyShape<D>(D);
+
+macro_rules! forward {
+    ( $($method:ident),* ) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+                self.0.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for AnyShape<D> {
+    type Error = D::Error;
+
+    forward!(
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_seq,
+        deserialize_map,
+        deserialize_identifier,
+        deserialize_ignored_any
+    );
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0.deserialize_newtype_struct(name, visitor)
+    }
+
+    // JSON deserializers reject objects for tuples before visiting them, so the shape is
+    // left to the document.
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0.deserialize_any(Positional(visitor))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0.deserialize_any(Positional(visitor))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0
+            .deserialize_struct(name, fields, Named { visitor, fields })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+/// Visitor for tuples, reading an object as the sequence of its values.
+struct Positional<V>(V);
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Positional<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.0.visit_unit()
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_seq(seq)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_seq(MapValues(map))
+    }
+}
+
+struct MapValues<A>(A);
+
+impl<'de, A: MapAccess<'de>> SeqAccess<'de> for MapValues<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, A::Error> {
+        match self.0.next_key::<IgnoredAny>()? {
+            Some(_) => self.0.next_value_seed(seed).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+/// Visitor for structs, reading an array as a map keyed by the field names.
+struct Named<V> {
+    visitor: V,
+    fields: &'static [&'static str],
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Named<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_map(map)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_map(SeqFields {
+            seq,
+            fields: self.fields,
+            index: 0,
+            value: None,
+        })
+    }
+}
+
+struct SeqFields<'de, A> {
+    seq: A,
+    fields: &'static [&'static str],
+    index: usize,
+    value: Option<Content<'de>>,
+}
+
+impl<'de, A: SeqAccess<'de>> MapAccess<'de> for SeqFields<'de, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        // Elements are buffered so fields past the end of the array count as missing.
+        let value = match self.seq.next_element::<Content<'de>>()? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        let field = match self.fields.get(self.index) {
+            Some(field) => *field,
+            None => {
+                return Err(de::Error::invalid_length(
+                    self.index + 1,
+                    &&*format!("at most {} params", self.fields.len()),
+                ))
+            }
+        };
+        self.index += 1;
+        self.value = Some(value);
+        seed.deserialize(BorrowedStrDeserializer::new(field))
+            .map(Some)
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, A::Error> {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(value.into_deserializer())
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.seq.size_hint()
+    }
+}
+
+/// Buffered value of any deserializer, keeping borrowed strings and bytes borrowed.
+enum Content<'de> {
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Str(&'de str),
+    String(String),
+    Bytes(&'de [u8]),
+    ByteBuf(Vec<u8>),
+    Unit,
+    Seq(Vec<Content<'de>>),
+    Map(Vec<(Content<'de>, Content<'de>)>),
+}
+
+impl<'de> Deserialize<'de> for Content<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ContentVisitor)
+    }
+}
+
+struct ContentVisitor;
+
+impl<'de> Visitor<'de> for ContentVisitor {
+    type Value = Content<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Content::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Content::I64(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Content::U64(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Content::F64(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Content::String(v.to_owned()))
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Content::Str(v))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Content::String(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Content::ByteBuf(v.to_owned()))
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        Ok(Content::Bytes(v))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(Content::ByteBuf(v))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Content::Unit)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Content::Unit)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        Content::deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        Content::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut elements = Vec::new();
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        Ok(Content::Seq(elements))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Content::Map(entries))
+    }
+}
+
+impl<'de, E: de::Error> IntoDeserializer<'de, E> for Content<'de> {
+    type Deserializer = ContentDeserializer<'de, E>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        ContentDeserializer {
+            content: self,
+            error: PhantomData,
+        }
+    }
+}
+
+struct ContentDeserializer<'de, E> {
+    content: Content<'de>,
+    error: PhantomData<E>,
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for ContentDeserializer<'de, E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::Bool(v) => visitor.visit_bool(v),
+            Content::I64(v) => visitor.visit_i64(v),
+            Content::U64(v) => visitor.visit_u64(v),
+            Content::F64(v) => visitor.visit_f64(v),
+            Content::Str(v) => visitor.visit_borrowed_str(v),
+            Content::String(v) => visitor.visit_string(v),
+            Content::Bytes(v) => visitor.visit_borrowed_bytes(v),
+            Content::ByteBuf(v) => visitor.visit_byte_buf(v),
+            Content::Unit => visitor.visit_unit(),
+            Content::Seq(v) => {
+                let mut seq = SeqDeserializer::new(v.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Content::Map(v) => {
+                let mut map = MapDeserializer::new(v.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::Unit => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        match self.content {
+            Content::Str(v) => visitor.visit_enum(BorrowedStrDeserializer::new(v)),
+            Content::String(v) => visitor.visit_enum(StringDeserializer::new(v)),
+            Content::Map(v) => visitor.visit_enum(MapAccessDeserializer::new(
+                MapDeserializer::new(v.into_iter()),
+            )),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::client::JsonRpcTransport;
+    use crate::config::JsonRpcConfig;
+    use crate::error::JsonRpcError;
+    use crate::{JsonRpcExtractor, JsonRpcRouter};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Transfer {
+        from: String,
+        #[serde(rename = "to_account")]
+        to: String,
+        amount: Option<u64>,
+        memo: Option<String>,
+    }
+
+    fn parse<T: for<'de> Deserialize<'de>>(json: &str) -> Result<T, String> {
+        serde_json::from_str::<Params<T>>(json)
+            .map(Params::into_inner)
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn struct_from_array() {
+        let transfer: Transfer = parse(r#"["a", "b", 5, "rent"]"#).unwrap();
+        assert_eq!(
+            transfer,
+            Transfer {
+                from: "a".to_owned(),
+                to: "b".to_owned(),
+                amount: Some(5),
+                memo: Some("rent".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn struct_from_owned_input() {
+        let Params(transfer): Params<Transfer> =
+            serde_json::from_value(json!(["a", "b", 5])).unwrap();
+        assert_eq!((transfer.to.as_str(), transfer.amount), ("b", Some(5)));
+
+        let Params(transfer): Params<Transfer> =
+            serde_json::from_reader(&br#"["a", "b", null, "rent"]"#[..]).unwrap();
+        assert_eq!(
+            (transfer.amount, transfer.memo.as_deref()),
+            (None, Some("rent"))
+        );
+
+        let Params(pair): Params<Pair> = serde_json::from_value(json!([1, 2])).unwrap();
+        assert_eq!((pair.a, pair.b), (1, 2));
+        assert!(serde_json::from_value::<Params<Pair>>(json!([1, 2, 3])).is_err());
+    }
+
+    #[test]
+    fn struct_from_array_borrows_strings() {
+        #[derive(Deserialize)]
+        struct Borrowed<'a> {
+            name: &'a str,
+            tags: Vec<String>,
+            kind: Kind,
+        }
+        #[derive(Debug, Deserialize, PartialEq)]
+        enum Kind {
+            Plain,
+            Weighted(u8),
+        }
+        let json = r#"["alice", ["x"], {"Weighted": 3}]"#;
+        let Params(borrowed): Params<Borrowed<'_>> = serde_json::from_str(json).unwrap();
+        assert_eq!((borrowed.name, borrowed.tags.len()), ("alice", 1));
+        assert_eq!(borrowed.kind, Kind::Weighted(3));
+        let Params(borrowed): Params<Borrowed<'_>> =
+            serde_json::from_str(r#"["bob", [], "Plain"]"#).unwrap();
+        assert_eq!(borrowed.kind, Kind::Plain);
+    }
+
+    #[test]
+    fn struct_from_short_array() {
+        let transfer: Transfer = parse(r#"["a", "b"]"#).unwrap();
+        assert_eq!((transfer.amount, transfer.memo), (None, None));
+
+        let error = parse::<Transfer>(r#"["a"]"#).unwrap_err();
+        assert!(error.contains("missing field `to_account`"), "{}", error);
+    }
+
+    #[test]
+    fn struct_from_long_array() {
+        let error = parse::<Transfer>(r#"["a", "b", 5, "rent", true]"#).unwrap_err();
+        assert!(
+            error.contains("invalid length 5, expected at most 4 params"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn struct_from_object_uses_renamed_fields() {
+        let transfer: Transfer = parse(r#"{"from": "a", "to_account": "b"}"#).unwrap();
+        assert_eq!(transfer.to, "b");
+        assert!(parse::<Transfer>(r#"{"from": "a", "to": "b"}"#).is_err());
+    }
+
+    #[test]
+    fn tuple_and_array_from_object() {
+        let pair: (String, i32) = parse(r#"{"name": "a", "count": 2}"#).unwrap();
+        assert_eq!(pair, ("a".to_owned(), 2));
+        let array: [i32; 3] = parse(r#"{"x": 1, "y": 2, "z": 3}"#).unwrap();
+        assert_eq!(array, [1, 2, 3]);
+        assert!(parse::<[i32; 3]>(r#"{"x": 1, "y": 2}"#).is_err());
+        assert!(parse::<(i32, i32)>(r#"{"x": 1, "y": 2, "z": 3}"#).is_err());
+    }
+
+    #[test]
+    fn nested_values_keep_their_shape() {
+        #[derive(Debug, Deserialize)]
+        struct Outer {
+            inner: (i32, i32),
+        }
+        assert_eq!(parse::<Outer>(r#"[[1, 2]]"#).unwrap().inner, (1, 2));
+        assert!(parse::<Outer>(r#"[{"a": 1, "b": 2}]"#).is_err());
+    }
+
+    #[test]
+    fn omitted_params() {
+        #[derive(Debug, Deserialize)]
+        struct Options {
+            verbose: Option<bool>,
+        }
+        let call = JsonRpcExtractor::from_slice(
+            br#"{"jsonrpc": "2.0", "method": "status", "id": 1}"#,
+            &JsonRpcConfig::default(),
+        )
+        .unwrap();
+        let Params(options): Params<Options> = call.try_parse_params().unwrap();
+        assert_eq!(options.verbose, None);
+        let Params(list): Params<Vec<i32>> = call.try_parse_params().unwrap();
+        assert!(list.is_empty());
+        assert!(call.try_parse_params::<Params<Transfer>>().is_err());
+    }
+
+    #[derive(Deserialize)]
+    struct Pair {
+        a: i32,
+        b: i32,
+    }
+
+    async fn add(Params(pair): Params<Pair>) -> Result<i32, JsonRpcError> {
+        Ok(pair.a + pair.b)
+    }
+
+    async fn call(router: &JsonRpcRouter, params: Value) -> Value {
+        let request = json!({"jsonrpc": "2.0", "method": "add", "params": params, "id": 1});
+        let response = router
+            .send(serde_json::to_vec(&request).unwrap())
+            .await
+            .unwrap();
+        serde_json::from_slice(&response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn params_through_router() {
+        let router = JsonRpcRouter::new().route("add", add);
+        assert_eq!(call(&router, json!([1, 2])).await["result"], 3);
+        assert_eq!(call(&router, json!({"a": 1, "b": 2})).await["result"], 3);
+        let response = call(&router, json!([1, 2, 3])).await;
+        assert_eq!(response["error"]["code"], -32602);
+    }
+}
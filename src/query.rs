@@ -0,0 +1,256 @@
+use axum::extract::{FromRequestParts, Query};
+use axum::http::request::Parts;
+use serde::de::IgnoredAny;
+use serde_json::value::RawValue;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+
+use crate::config::JsonRpcConfig;
+use crate::error::JsonRpcError;
+use crate::{invalid_request, Id, JsonRpcExtractor, JsonRpcResponse};
+
+#[derive(Debug)]
+/// Parses a JSON-RPC call from the query string of a `GET` request, e.g.
+/// `?method=add&id=1&params=[1,2]`, and validates it like [`JsonRpcExtractor`].
+///
+/// `params` holds URL-encoded JSON or base64 (standard or URL-safe, padding optional)
+/// encoded JSON. `jsonrpc` defaults to `2.0`; without an `id` the call is a notification.
+/// A cache-busting `_` parameter, e.g. `&_=1700000000`, is ignored even in
+/// [strict](crate::JsonRpcConfig::strict) mode.
+/// ```rust
+/// use axum::{routing::get, Router};
+/// use axum_jrpc::{JrpcResult, JsonRpcQueryExtractor};
+///
+/// async fn handler(JsonRpcQueryExtractor(req): JsonRpcQueryExtractor) -> JrpcResult {
+///   match req.method() {
+///     "add" => {
+///       let params: [i32;2] = req.parse_params()?;
+///       Ok(req.success(params[0] + params[1]))
+///     }
+///     m => Ok(req.method_not_found(m)),
+///   }
+/// }
+///
+/// let app: Router = Router::new().route("/", get(handler));
+/// ```
+pub struct JsonRpcQueryExtractor(pub JsonRpcExtractor);
+
+impl JsonRpcQueryExtractor {
+    pub fn into_inner(self) -> JsonRpcExtractor {
+        self.0
+    }
+}
+
+impl Deref for JsonRpcQueryExtractor {
+    type Target = JsonRpcExtractor;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for JsonRpcQueryExtractor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for JsonRpcQueryExtractor
+where
+    S: Send + Sync,
+{
+    type Rejection = JsonRpcResponse;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let config = JsonRpcConfig::from_extensions(&parts.extensions);
+        let query = match Query::<HashMap<String, String>>::from_request_parts(parts, state).await {
+            Ok(a) => a.0,
+            Err(e) => return Err(invalid_request(e.body_text())),
+        };
+
+        // Built as raw JSON text so the id and params reach the extractor as written.
+        let mut request = HashMap::new();
+        request.insert("jsonrpc".to_owned(), to_raw_value("2.0"));
+        for (key, value) in query {
+            if key == "_" {
+                continue;
+            }
+            let value = match key.as_str() {
+                // Unquoted ids like `id=abc` are taken as strings.
+                "id" => match serde_json::from_str::<Id>(&value) {
+                    Ok(_) => RawValue::from_string(value).expect("id is valid JSON"),
+                    Err(_) => to_raw_value(&value),
+                },
+                "params" => parse_params(value)?,
+                _ => to_raw_value(&value),
+            };
+            request.insert(key, value);
+        }
+        let request = serde_json::to_vec(&request).expect("query is serializable");
+        JsonRpcExtractor::from_slice(&request, &config).map(Self)
+    }
+}
+
+fn to_raw_value(value: &str) -> Box<RawValue> {
+    serde_json::value::to_raw_value(value).expect("string is serializable")
+}
+
+/// Parses `params` as URL-decoded JSON, falling back to base64 encoded JSON.
+fn parse_params(params: String) -> Result<Box<RawValue>, JsonRpcResponse> {
+    if serde_json::from_str::<IgnoredAny>(&params).is_ok() {
+        return Ok(RawValue::from_string(params).expect("params are valid JSON"));
+    }
+    let decoded = decode_base64(&params)
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .ok_or_else(|| {
+            parse_error("`params` is neither JSON nor base64 encoded JSON".to_owned())
+        })?;
+    RawValue::from_string(decoded)
+        .map_err(|e| parse_error(format!("Failed to parse `params` as JSON: {}", e)))
+}
+
+fn parse_error(message: String) -> JsonRpcResponse {
+    JsonRpcResponse::error(Id::Null, JsonRpcError::parse_error(message))
+}
+
+/// Decodes standard or URL-safe base64, with or without padding.
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let unpadded = input.trim_end_matches('=');
+    let padding = input.len() - unpadded.len();
+    if padding > 0 && (padding > 2 || !input.len().is_multiple_of(4)) {
+        return None;
+    }
+    let input = unpadded.as_bytes();
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in input {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    // A single leftover character can't encode a whole byte, and the bits
+    // left over from the last character must be zero.
+    if bits >= 6 || buffer & ((1 << bits) - 1) != 0 {
+        return None;
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Request;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::JsonRpcAnswer;
+
+    async fn extract(query: &str) -> Result<JsonRpcExtractor, JsonRpcResponse> {
+        let request = Request::get(format!("/?{}", query)).body(()).unwrap();
+        let (mut parts, _) = request.into_parts();
+        JsonRpcQueryExtractor::from_request_parts(&mut parts, &())
+            .await
+            .map(JsonRpcQueryExtractor::into_inner)
+    }
+
+    fn params(request: &JsonRpcExtractor) -> Value {
+        serde_json::from_str(request.params().unwrap().get()).unwrap()
+    }
+
+    fn error_code(response: JsonRpcResponse) -> i32 {
+        match response.result {
+            JsonRpcAnswer::Error(error) => error.code(),
+            JsonRpcAnswer::Result(result) => panic!("expected an error, got {}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn url_encoded_json_params() {
+        let request = extract("method=add&id=1&params=%5B1%2C2%5D").await.unwrap();
+        assert_eq!(request.method(), "add");
+        assert_eq!(request.get_answer_id(), Id::from(1));
+        assert_eq!(params(&request), json!([1, 2]));
+
+        let request = extract("method=a&params=%7B%22b%22%3A%22c%22%7D")
+            .await
+            .unwrap();
+        assert!(request.is_notification());
+        assert_eq!(params(&request), json!({"b": "c"}));
+    }
+
+    #[tokio::test]
+    async fn base64_params() {
+        // Padding is optional, and may itself be URL-encoded.
+        for encoded in ["eyJhIjoiPj8ifQ%3D%3D", "eyJhIjoiPj8ifQ", "eyJhIjoiPj8ifQ=="] {
+            let request = extract(&format!("method=a&id=1&params={}", encoded))
+                .await
+                .unwrap();
+            assert_eq!(params(&request), json!({"a": ">?"}), "{}", encoded);
+        }
+        // `[">>>"]` encodes to `+` in standard and `-` in URL-safe base64.
+        for encoded in ["WyI%2BPj4iXQ%3D%3D", "WyI-Pj4iXQ", "WyI%2BPj4iXQ"] {
+            let request = extract(&format!("method=a&id=1&params={}", encoded))
+                .await
+                .unwrap();
+            assert_eq!(params(&request), json!([">>>"]), "{}", encoded);
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_params_are_parse_errors() {
+        for params in [
+            "%5B1%2C",
+            "not*base64",
+            "WyJ",
+            "WyI%2BPj4iXQ%3D",
+            "bm90IGpzb24",
+        ] {
+            let response = extract(&format!("method=a&id=1&params={}", params))
+                .await
+                .unwrap_err();
+            assert_eq!(error_code(response), -32700, "{}", params);
+        }
+    }
+
+    #[test]
+    fn base64_rejects_non_canonical_input() {
+        assert_eq!(decode_base64("YQ==").unwrap(), b"a");
+        assert_eq!(decode_base64("YQ").unwrap(), b"a");
+        // `YR` decodes to `a` too, but sets bits that don't belong to any byte.
+        assert_eq!(decode_base64("YR"), None);
+        assert_eq!(decode_base64("YQ="), None);
+        assert_eq!(decode_base64("YQ==="), None);
+        assert_eq!(decode_base64("Y"), None);
+        assert_eq!(decode_base64("").unwrap(), b"");
+    }
+
+    #[tokio::test]
+    async fn unquoted_ids_are_strings() {
+        let request = extract("method=a&id=abc").await.unwrap();
+        assert_eq!(request.get_answer_id(), Id::from("abc"));
+        let request = extract("method=a&id=%22abc%22").await.unwrap();
+        assert_eq!(request.get_answer_id(), Id::from("abc"));
+        let request = extract("method=a&id=12").await.unwrap();
+        assert_eq!(request.get_answer_id(), Id::from(12));
+    }
+
+    #[tokio::test]
+    async fn cache_busters_are_ignored() {
+        let request = extract("method=a&id=1&_=1700000000").await.unwrap();
+        assert!(request.extra().is_empty());
+        let response = extract("method=a&id=1&foo=1").await.unwrap_err();
+        assert_eq!(error_code(response), -32600);
+    }
+}
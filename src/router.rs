@@ -0,0 +1,480 @@
+//! Dispatching calls to handlers by method name.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use axum::body::HttpBody;
+use axum::extract::FromRequest;
+use axum::http::Request;
+use axum::response::{IntoResponse, Response};
+use axum::BoxError;
+use futures_util::future::BoxFuture;
+use tower::ServiceExt;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::error::JsonRpcError;
+use crate::handler::{JsonRpcCall, JsonRpcHandler};
+use crate::{JsonRpcBatchExtractor, JsonRpcResponse};
+
+/// Boxed handler of a method, shared between threads without locking.
+///
+/// This is the service wrapped by the layers passed to [`JsonRpcRouter::layer`].
+#[derive(Clone)]
+pub struct Route(Arc<dyn Fn(JsonRpcCall) -> BoxFuture<'static, JsonRpcResponse> + Send + Sync>);
+
+impl Route {
+    fn new<F, Fut>(handler: F) -> Self
+    where
+        F: Fn(JsonRpcCall) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = JsonRpcResponse> + Send + 'static,
+    {
+        Route(Arc::new(move |call| Box::pin(handler(call))))
+    }
+
+    fn call(&self, call: JsonRpcCall) -> BoxFuture<'static, JsonRpcResponse> {
+        (self.0)(call)
+    }
+
+    fn layer<L>(self, layer: &L) -> Route
+    where
+        L: Layer<Route>,
+        L::Service:
+            Service<JsonRpcCall, Response = JsonRpcResponse> + Clone + Send + Sync + 'static,
+        <L::Service as Service<JsonRpcCall>>::Error: Into<BoxError>,
+        <L::Service as Service<JsonRpcCall>>::Future: Send + 'static,
+    {
+        let service = layer.layer(self);
+        Route::new(move |call: JsonRpcCall| {
+            let id = call.request.get_answer_id();
+            let future = service.clone().oneshot(call);
+            async move {
+                future.await.unwrap_or_else(|e| {
+                    JsonRpcResponse::error(id, JsonRpcError::internal_error(e.into().to_string()))
+                })
+            }
+        })
+    }
+}
+
+impl std::fmt::Debug for Route {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Route").finish_non_exhaustive()
+    }
+}
+
+impl Service<JsonRpcCall> for Route {
+    type Response = JsonRpcResponse;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<JsonRpcResponse, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, call: JsonRpcCall) -> Self::Future {
+        let future = Route::call(self, call);
+        Box::pin(async move { Ok(future.await) })
+    }
+}
+
+#[derive(Clone)]
+/// Routes calls to async handlers registered by method name.
+///
+/// Requests are parsed with [`JsonRpcBatchExtractor`], so batches and notifications are
+/// handled, and unknown methods are answered with a `Method not found` error unless a
+/// [`fallback`](JsonRpcRouter::fallback) is set.
+/// Responses are written with the protocol version of the call.
+/// Handlers are described in the [`handler`](crate::handler) module.
+/// ```rust
+/// use axum::Router;
+/// use axum_jrpc::{JrpcResult, JsonRpcExtractor, JsonRpcRouter};
+///
+/// async fn add(req: JsonRpcExtractor) -> JrpcResult {
+///   let params: [i32;2] = req.parse_params()?;
+///   Ok(req.success(params[0] + params[1]))
+/// }
+///
+/// let rpc = JsonRpcRouter::new().route("add", add);
+/// let app: Router = Router::new().route_service("/", rpc);
+/// ```
+pub struct JsonRpcRouter<S = ()> {
+    routes: Arc<HashMap<String, Route>>,
+    fallback: Option<Route>,
+    state: S,
+}
+
+impl JsonRpcRouter {
+    pub fn new() -> Self {
+        Self::with_state(())
+    }
+}
+
+impl Default for JsonRpcRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> JsonRpcRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    /// Creates a router whose handlers can extract `state` with
+    /// [`State`](axum::extract::State).
+    pub fn with_state(state: S) -> Self {
+        Self {
+            routes: Arc::default(),
+            fallback: None,
+            state,
+        }
+    }
+
+    /// Registers `handler` for calls to `method`.
+    ///
+    /// # Panics
+    ///
+    /// If a handler is already registered for `method`.
+    #[track_caller]
+    pub fn route<H, T>(mut self, method: &str, handler: H) -> Self
+    where
+        H: JsonRpcHandler<T, S>,
+    {
+        let route = self.handler_route(handler);
+        self.insert_route(method.to_owned(), route);
+        self
+    }
+
+    /// Sets the handler for calls to unknown methods, replacing the default
+    /// `Method not found` error. Errors meant for the client should still be
+    /// [`MethodNotFound`].
+    /// ```rust
+    /// use axum_jrpc::error::MethodNotFound;
+    /// use axum_jrpc::handler::Method;
+    /// # use axum_jrpc::{JrpcResult, JsonRpcExtractor, JsonRpcRouter};
+    /// # async fn get_balance(req: JsonRpcExtractor) -> JrpcResult {
+    /// #   Ok(req.success(0))
+    /// # }
+    ///
+    /// let rpc = JsonRpcRouter::new().route("eth_getBalance", get_balance);
+    /// let methods = rpc.methods().map(str::to_owned).collect::<Vec<_>>();
+    /// let rpc = rpc.fallback(move |Method(method): Method| {
+    ///     let error = MethodNotFound::new(&method).suggest(&methods);
+    ///     async move { Err::<(), _>(error) }
+    /// });
+    /// ```
+    pub fn fallback<H, T>(mut self, handler: H) -> Self
+    where
+        H: JsonRpcHandler<T, S>,
+    {
+        self.fallback = Some(self.handler_route(handler));
+        self
+    }
+
+    fn handler_route<H, T>(&self, handler: H) -> Route
+    where
+        H: JsonRpcHandler<T, S>,
+    {
+        let state = self.state.clone();
+        Route::new(move |call| handler.clone().call(call, state.clone()))
+    }
+
+    /// Adds all methods of `other` to this router. `other` keeps its own state, its
+    /// fallback is dropped.
+    ///
+    /// # Panics
+    ///
+    /// If both routers have a handler for the same method.
+    #[track_caller]
+    pub fn merge<S2>(self, other: JsonRpcRouter<S2>) -> Self {
+        self.nest_with(other, |method| method)
+    }
+
+    /// Adds all methods of `other` to this router, named `{namespace}{separator}{method}`.
+    /// `other` keeps its own state, its fallback is dropped.
+    /// ```rust
+    /// # use axum_jrpc::{JrpcResult, JsonRpcExtractor, JsonRpcRouter};
+    /// # async fn get_balance(req: JsonRpcExtractor) -> JrpcResult {
+    /// #   Ok(req.success(0))
+    /// # }
+    /// let eth = JsonRpcRouter::new().route("getBalance", get_balance);
+    /// let rpc = JsonRpcRouter::new().nest("eth", "_", eth);
+    /// assert!(rpc.has_route("eth_getBalance"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If both routers have a handler for the same method after prefixing.
+    #[track_caller]
+    pub fn nest<S2>(self, namespace: &str, separator: &str, other: JsonRpcRouter<S2>) -> Self {
+        self.nest_with(other, |method| {
+            format!("{}{}{}", namespace, separator, method)
+        })
+    }
+
+    #[track_caller]
+    fn nest_with<S2>(mut self, other: JsonRpcRouter<S2>, name: impl Fn(String) -> String) -> Self {
+        let routes = Arc::try_unwrap(other.routes).unwrap_or_else(|routes| (*routes).clone());
+        for (method, route) in routes {
+            self.insert_route(name(method), route);
+        }
+        self
+    }
+
+    #[track_caller]
+    fn insert_route(&mut self, method: String, route: Route) {
+        let routes = Arc::make_mut(&mut self.routes);
+        if routes.contains_key(&method) {
+            panic!(
+                "Overlapping method route. `{}` is already registered",
+                method
+            );
+        }
+        routes.insert(method, route);
+    }
+
+    /// Wraps the handlers of all methods registered so far in `layer`. Methods and the
+    /// fallback added afterwards are not affected, so a group of methods can be layered
+    /// in its own router and then [`nest`](JsonRpcRouter::nest)ed.
+    ///
+    /// Errors returned by the layered service are answered with an `Internal error`.
+    /// ```rust
+    /// use axum_jrpc::handler::JsonRpcCall;
+    /// use tower::util::MapRequestLayer;
+    /// # use axum_jrpc::{JrpcResult, JsonRpcExtractor, JsonRpcRouter};
+    /// # async fn shutdown(req: JsonRpcExtractor) -> JrpcResult {
+    /// #   Ok(req.success(true))
+    /// # }
+    ///
+    /// let admin = JsonRpcRouter::new()
+    ///     .route("shutdown", shutdown)
+    ///     .layer(MapRequestLayer::new(|call: JsonRpcCall| {
+    ///         println!("admin call: {}", call.request.method());
+    ///         call
+    ///     }));
+    /// let rpc = JsonRpcRouter::new().nest("admin", "_", admin);
+    /// ```
+    pub fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route>,
+        L::Service:
+            Service<JsonRpcCall, Response = JsonRpcResponse> + Clone + Send + Sync + 'static,
+        <L::Service as Service<JsonRpcCall>>::Error: Into<BoxError>,
+        <L::Service as Service<JsonRpcCall>>::Future: Send + 'static,
+    {
+        let routes = Arc::try_unwrap(self.routes).unwrap_or_else(|routes| (*routes).clone());
+        self.routes = Arc::new(
+            routes
+                .into_iter()
+                .map(|(method, route)| (method, route.layer(&layer)))
+                .collect(),
+        );
+        self
+    }
+
+    /// Returns `true` if a handler is registered for `method`.
+    pub fn has_route(&self, method: &str) -> bool {
+        self.routes.contains_key(method)
+    }
+
+    /// Returns the registered method names, in no particular order.
+    pub fn methods(&self) -> impl Iterator<Item = &str> {
+        self.routes.keys().map(String::as_str)
+    }
+
+    /// Runs the handler registered for the call's method.
+    pub async fn dispatch(&self, call: JsonRpcCall) -> JsonRpcResponse {
+        let notification = call.request.is_notification();
+        let version = call.request.version();
+        let response = match self.routes.get(call.request.method()) {
+            Some(route) => route.call(call).await,
+            None => match &self.fallback {
+                Some(fallback) => fallback.call(call).await,
+                None => call.request.method_not_found(call.request.method()),
+            },
+        };
+        if notification {
+            return JsonRpcResponse::notification();
+        }
+        response.with_version(version)
+    }
+}
+
+impl<S> std::fmt::Debug for JsonRpcRouter<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonRpcRouter")
+            .field("methods", &self.routes.keys().collect::<Vec<_>>())
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+impl<S, B> Service<Request<B>> for JsonRpcRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let router = self.clone();
+        let headers = Arc::new(req.headers().clone());
+        Box::pin(async move {
+            let batch = match JsonRpcBatchExtractor::from_request(req, &()).await {
+                Ok(batch) => batch,
+                Err(e) => return Ok(e.into_response()),
+            };
+            let router = &router;
+            let headers = &headers;
+            let response = batch
+                .handle(|request| async move {
+                    let call = JsonRpcCall {
+                        request,
+                        headers: Arc::clone(headers),
+                    };
+                    Ok(router.dispatch(call).await)
+                })
+                .await;
+            Ok(response.into_response())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::extract::State;
+    use serde_json::{json, Value};
+    use tokio::sync::Barrier;
+    use tower::util::MapRequestLayer;
+
+    use super::*;
+    use crate::client::JsonRpcTransport;
+    use crate::error::MethodNotFound;
+    use crate::handler::Method;
+    use crate::Id;
+
+    async fn send<S: Clone + Send + Sync + 'static>(
+        router: &JsonRpcRouter<S>,
+        request: Value,
+    ) -> Value {
+        let response = router
+            .send(serde_json::to_vec(&request).unwrap())
+            .await
+            .unwrap();
+        serde_json::from_slice(&response).unwrap()
+    }
+
+    #[test]
+    fn routers_are_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Route>();
+        assert_send_sync::<JsonRpcRouter>();
+    }
+
+    #[tokio::test]
+    async fn calls_to_a_method_run_concurrently() {
+        async fn wait(State(barrier): State<Arc<Barrier>>, id: Id) -> Result<Id, JsonRpcError> {
+            barrier.wait().await;
+            Ok(id)
+        }
+        let router = JsonRpcRouter::with_state(Arc::new(Barrier::new(2))).route("wait", wait);
+        let response = send(
+            &router,
+            json!([
+                {"jsonrpc": "2.0", "method": "wait", "id": 1},
+                {"jsonrpc": "2.0", "method": "wait", "id": 2},
+            ]),
+        )
+        .await;
+        assert_eq!(
+            response,
+            json!([
+                {"jsonrpc": "2.0", "result": 1, "id": 1},
+                {"jsonrpc": "2.0", "result": 2, "id": 2},
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn layers_wrap_existing_routes() {
+        async fn method(Method(method): Method) -> Result<String, JsonRpcError> {
+            Ok(method)
+        }
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let router = JsonRpcRouter::new()
+            .route("layered", method)
+            .layer(MapRequestLayer::new(move |call: JsonRpcCall| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                call
+            }))
+            .route("plain", method);
+        let request = json!({"jsonrpc": "2.0", "method": "layered", "id": 1});
+        assert_eq!(send(&router, request).await["result"], "layered");
+        let request = json!({"jsonrpc": "2.0", "method": "plain", "id": 1});
+        assert_eq!(send(&router, request).await["result"], "plain");
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    async fn unit() -> Result<(), JsonRpcError> {
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Overlapping method route. `add` is already registered")]
+    fn merging_overlapping_routes_panics() {
+        let other = JsonRpcRouter::new().route("add", unit);
+        let _ = JsonRpcRouter::new().route("add", unit).merge(other);
+    }
+
+    #[test]
+    #[should_panic(expected = "Overlapping method route. `math.add` is already registered")]
+    fn nesting_onto_an_existing_route_panics() {
+        let other = JsonRpcRouter::new().route("add", unit);
+        let _ = JsonRpcRouter::new()
+            .route("math.add", unit)
+            .nest("math", ".", other);
+    }
+
+    #[test]
+    fn nested_routes_only_collide_after_prefixing() {
+        let other = JsonRpcRouter::new().route("add", unit);
+        let router = JsonRpcRouter::new()
+            .route("add", unit)
+            .nest("math", ".", other);
+        assert!(router.has_route("add"));
+        assert!(router.has_route("math.add"));
+    }
+
+    #[tokio::test]
+    async fn fallbacks_send_suggestions_as_data() {
+        let router = JsonRpcRouter::new()
+            .route("get_balance", unit)
+            .route("get_block", unit);
+        let methods = router.methods().map(str::to_owned).collect::<Vec<_>>();
+        let router = router.fallback(move |Method(method): Method| {
+            let error = MethodNotFound::new(&method).suggest(&methods);
+            async move { Err::<(), _>(error) }
+        });
+        let request = json!({"jsonrpc": "2.0", "method": "get_balanse", "id": 1});
+        let response = send(&router, request).await;
+        assert_eq!(response["error"]["code"], -32601);
+        assert_eq!(
+            response["error"]["data"],
+            json!({"suggestions": ["get_balance"]})
+        );
+    }
+}